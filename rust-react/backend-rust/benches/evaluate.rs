@@ -0,0 +1,33 @@
+// Table evaluator vs. the 21-subset reference. Run: cargo bench --bench evaluate
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use texas_equity_api::cards::{draw_indices, seeded_rng};
+use texas_equity_api::evaluate::{best_hand_score_7_indices, best_hand_score_7_reference, init_tables};
+
+fn random_hands(n: usize) -> Vec<[usize; 7]> {
+    let mut rng = seeded_rng(1);
+    (0..n)
+        .map(|_| {
+            let mut deck: Vec<usize> = (0..52).collect();
+            let mut idx = [0usize; 7];
+            draw_indices(&mut deck, 7, &mut rng, &mut idx);
+            idx
+        })
+        .collect()
+}
+
+fn bench_eval7(c: &mut Criterion) {
+    init_tables();
+    let hands = random_hands(10_000);
+    let mut group = c.benchmark_group("eval7_10k");
+    group.bench_function("reference", |b| {
+        b.iter(|| hands.iter().map(|h| best_hand_score_7_reference(black_box(h)) as u64).sum::<u64>())
+    });
+    group.bench_function("tables", |b| {
+        b.iter(|| hands.iter().map(|h| best_hand_score_7_indices(black_box(h)) as u64).sum::<u64>())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_eval7);
+criterion_main!(benches);
@@ -2,6 +2,8 @@
 
 use std::collections::HashMap;
 use std::path::PathBuf;
+use texas_equity_api::preflop_table::PreflopRow;
+use texas_equity_api::simulate::{round6, StdErrors};
 use texas_equity_api::{cards::Card, evaluate, hand_class, simulate};
 
 const DEFAULT_TRIALS: u64 = 2_000_000;
@@ -130,11 +132,19 @@ fn exact_heads_up_preflop(hero: &[Card]) -> Result<simulate::SimResult, String>
         return Err("no exact states enumerated".to_string());
     }
     Ok(simulate::SimResult {
+        std_err: StdErrors::default(),
         win: win as f64 / total as f64,
         tie: tie as f64 / total as f64,
         lose: lose as f64 / total as f64,
+        // heads-up: a chop is half the pot
+        equity: (win as f64 + tie as f64 / 2.0) / total as f64,
         trials: total,
         elapsed_ms: 0,
+        seats: Vec::new(),
+        hero_categories: [0.0; 9],
+        opponent_categories: [0.0; 9],
+        hi_lo: None,
+        stratified: false,
     })
 }
 
@@ -193,17 +203,21 @@ fn main() {
             } else {
                 simulate::simulate(players, &cards, &board, trials, None)
             };
+            let ci = result.ci95();
             let row = PreflopRow {
-                win: (result.win * 1e6).round() / 1e6,
-                tie: (result.tie * 1e6).round() / 1e6,
-                lose: (result.lose * 1e6).round() / 1e6,
+                win: round6(result.win),
+                tie: round6(result.tie),
+                lose: round6(result.lose),
+                equity: Some(round6(result.equity)),
+                std_err: Some(result.std_err.map(round6)),
+                ci95: Some(ci.map(round6)),
             };
             metric_per_hand = result.trials;
             data.get_mut(&players.to_string())
                 .unwrap()
                 .insert(hand_class_str.clone(), row);
             done += 1;
-            if done % 100 == 0 {
+            if done.is_multiple_of(100) {
                 eprintln!("Progress: {}/{} ({} @ {}p)", done, total, hand_class_str, players);
             }
         }
@@ -230,10 +244,3 @@ fn main() {
         start.elapsed().as_secs_f64()
     );
 }
-
-#[derive(serde::Serialize)]
-struct PreflopRow {
-    win: f64,
-    tie: f64,
-    lose: f64,
-}
@@ -1,83 +1,530 @@
 // Card parsing, validation, deck building, partial Fisher–Yates.
-// Format: "As", "Td", "7h" (Rank: A,K,Q,J,T,9..2 / Suit: s,h,d,c)
+// Format: "As", "Td", "7h" (Rank: A,K,Q,J,T,9..2 / Suit: s,h,d,c); the parser also takes "10", ♠♥♦♣ and "AsKd".
 
-const RANKS: &str = "AKQJT98765432";
+use crate::game::Game;
+use crate::rng::{RngAlgorithm, SimRng, UniformRng};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Rank characters indexed by rank value (0=2 .. 12=A).
+const RANKS: &str = "23456789TJQKA";
 const SUITS: &str = "shdc";
 
-#[derive(Debug, Clone, Copy)]
+/// Card rank. The discriminant is the rank value used everywhere (0=2 .. 12=A), so
+/// `rank as usize` indexes rank tables and `Ord` means "higher rank is greater".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Rank {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    /// All ranks, deuce first.
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    /// Rank for a value 0..13 (0 = deuce). Panics on larger values.
+    pub fn from_index(i: usize) -> Rank {
+        Self::ALL[i]
+    }
+
+    /// "A", "K", .. "T", "9" .. "2", case-insensitive.
+    pub fn from_char(ch: char) -> Option<Rank> {
+        RANKS.find(ch.to_ascii_uppercase()).map(Rank::from_index)
+    }
+
+    pub fn to_char(self) -> char {
+        RANKS.as_bytes()[self as usize] as char
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// Card suit, in card-index order (spades 0 .. clubs 3).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Suit {
+    Spades,
+    Hearts,
+    Diamonds,
+    Clubs,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+    /// Suit for a value 0..4. Panics on larger values.
+    pub fn from_index(i: usize) -> Suit {
+        Self::ALL[i]
+    }
+
+    /// "s", "h", "d", "c" (case-insensitive) or a suit symbol, filled or outline.
+    pub fn from_char(ch: char) -> Option<Suit> {
+        match ch {
+            '♠' | '♤' => Some(Suit::Spades),
+            '♥' | '♡' => Some(Suit::Hearts),
+            '♦' | '♢' => Some(Suit::Diamonds),
+            '♣' | '♧' => Some(Suit::Clubs),
+            _ => SUITS.find(ch.to_ascii_lowercase()).map(Suit::from_index),
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        SUITS.as_bytes()[self as usize] as char
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// A card. Parses from and displays as "As", "Td"; serializes as that string.
+/// Ordered by rank, then suit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Card {
-    pub rank: u8,  // 0=2, 12=A
-    pub suit: u8,  // 0=s,1=h,2=d,3=c
+    pub rank: Rank,
+    pub suit: Suit,
 }
 
 impl Card {
+    pub fn new(rank: Rank, suit: Suit) -> Self {
+        Card { rank, suit }
+    }
     pub fn to_idx(&self) -> usize {
         (self.suit as usize) * 13 + (self.rank as usize)
     }
     pub fn from_idx(idx: usize) -> Self {
         Card {
-            suit: (idx / 13) as u8,
-            rank: (idx % 13) as u8,
+            suit: Suit::from_index(idx / 13),
+            rank: Rank::from_index(idx % 13),
         }
     }
 }
 
-#[derive(Debug, serde::Serialize)]
+impl FromStr for Card {
+    type Err = ValidationError;
+
+    /// Exactly one card, in any form `parse_cards` accepts ("As", "td", "10h", "A♠").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cards = parse_cards(s)?;
+        match cards[..] {
+            [card] => Ok(card),
+            _ => Err(ValidationError {
+                error: "invalid_card_length".into(),
+                details: Some(format!("Expected one card (e.g. As, Td), got {}: {}", cards.len(), s)),
+                card: Some(s.into()),
+                position: None,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.rank, self.suit)
+    }
+}
+
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A list of cards, e.g. a player's hand or a board. Parses from whitespace-separated
+/// cards ("As Kd") and displays the same way; serializes as an array of card strings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Hand(pub Vec<Card>);
+
+impl FromStr for Hand {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_cards(s).map(Hand)
+    }
+}
+
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, c) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::ops::Deref for Hand {
+    type Target = [Card];
+
+    fn deref(&self) -> &[Card] {
+        &self.0
+    }
+}
+
+/// A set of cards as a 52-bit mask over card indices (bit `suit * 13 + rank`). Copy,
+/// allocation-free, and iterates in index order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CardSet(u64);
+
+impl CardSet {
+    pub const EMPTY: CardSet = CardSet(0);
+    /// The full 52-card deck.
+    pub const FULL: CardSet = CardSet((1u64 << 52) - 1);
+
+    /// Every card of rank `lowest_rank` or higher (the short deck starts at six).
+    pub fn deck(lowest_rank: Rank) -> CardSet {
+        let per_suit = ((1u64 << 13) - 1) & !((1u64 << lowest_rank as u64) - 1);
+        CardSet((0..4).fold(0, |m, s| m | (per_suit << (13 * s))))
+    }
+
+    pub fn from_mask(mask: u64) -> CardSet {
+        CardSet(mask & Self::FULL.0)
+    }
+
+    pub fn mask(self) -> u64 {
+        self.0
+    }
+
+    /// Set of card indices (0..52).
+    pub fn from_indices<'a>(indices: impl IntoIterator<Item = &'a usize>) -> CardSet {
+        CardSet(indices.into_iter().fold(0, |m, &i| m | (1u64 << i)))
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, card: Card) -> bool {
+        self.contains_idx(card.to_idx())
+    }
+
+    pub fn contains_idx(self, idx: usize) -> bool {
+        self.0 & (1u64 << idx) != 0
+    }
+
+    /// Add a card; returns false if it was already present.
+    pub fn insert(&mut self, card: Card) -> bool {
+        self.insert_idx(card.to_idx())
+    }
+
+    pub fn insert_idx(&mut self, idx: usize) -> bool {
+        let bit = 1u64 << idx;
+        let fresh = self.0 & bit == 0;
+        self.0 |= bit;
+        fresh
+    }
+
+    pub fn remove_idx(&mut self, idx: usize) {
+        self.0 &= !(1u64 << idx);
+    }
+
+    pub fn union(self, other: CardSet) -> CardSet {
+        CardSet(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: CardSet) -> CardSet {
+        CardSet(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: CardSet) -> CardSet {
+        CardSet(self.0 & !other.0)
+    }
+
+    pub fn is_disjoint(self, other: CardSet) -> bool {
+        self.0 & other.0 == 0
+    }
+
+    /// Card indices in ascending order.
+    pub fn indices(self) -> impl Iterator<Item = usize> {
+        let mut m = self.0;
+        std::iter::from_fn(move || {
+            if m == 0 {
+                return None;
+            }
+            let i = m.trailing_zeros() as usize;
+            m &= m - 1;
+            Some(i)
+        })
+    }
+
+    /// Cards in index order.
+    pub fn iter(self) -> impl Iterator<Item = Card> {
+        self.indices().map(Card::from_idx)
+    }
+
+    /// Remove and return a uniformly random card index, None when empty.
+    pub fn draw(&mut self, rng: &mut impl UniformRng) -> Option<usize> {
+        let n = self.len();
+        if n == 0 {
+            return None;
+        }
+        let i = select_bit(self.0, rng.below(n) as u32);
+        self.remove_idx(i);
+        Some(i)
+    }
+
+    /// Fill `out` with distinct cards drawn uniformly from the set (which is left as is),
+    /// via partial Fisher–Yates over the set's indices in a stack buffer. Panics if the
+    /// set has fewer than `out.len()` cards.
+    pub fn draw_into(self, rng: &mut impl UniformRng, out: &mut [usize]) {
+        let mut deck = [0usize; 52];
+        let mut len = 0;
+        for i in self.indices() {
+            deck[len] = i;
+            len += 1;
+        }
+        assert!(out.len() <= len, "cannot draw {} cards from {}", out.len(), len);
+        draw_indices(&mut deck[..len], out.len(), rng, out);
+    }
+
+    /// Fill `out` with distinct random cards of this set, positioned by a shuffle of
+    /// `order` (card indices covering the set, e.g. the game's whole deck): slot `i` gets
+    /// the `i`-th shuffled card, or, when that card is not in the set, the next unused set
+    /// card from past the end of `out`. The shuffle (partial Fisher–Yates on a stack copy)
+    /// does not depend on the set and always takes `out.len()` plus (`order.len()` - set
+    /// size) random draws. So two sets of the same size drawn against the same `order` with
+    /// the same RNG state get the same card in every slot where both contain it, trial after
+    /// trial: common random numbers for comparing deals. Panics if the set has fewer than
+    /// `out.len()` cards of `order`.
+    pub fn draw_shuffled(self, order: &[usize], rng: &mut impl UniformRng, out: &mut [usize]) {
+        let mut deck = [0usize; 52];
+        deck[..order.len()].copy_from_slice(order);
+        let deck = &mut deck[..order.len()];
+        let steps = (out.len() + order.len() - self.len()).min(order.len());
+        for i in 0..steps {
+            let j = i + rng.below(deck.len() - i);
+            deck.swap(i, j);
+        }
+        let mut spare = deck[out.len().min(steps)..steps].iter().filter(|&&c| self.contains_idx(c));
+        for (slot, &c) in out.iter_mut().zip(deck.iter()) {
+            *slot = if self.contains_idx(c) {
+                c
+            } else {
+                *spare.next().expect("set has enough cards to draw")
+            };
+        }
+    }
+}
+
+/// Position of the `k`-th lowest set bit of `m` (k counted from 0), by halving the
+/// search window instead of clearing bits one by one.
+fn select_bit(mut m: u64, mut k: u32) -> usize {
+    let mut pos = 0;
+    for width in [32, 16, 8, 4, 2, 1] {
+        let low = m & ((1u64 << width) - 1);
+        let c = low.count_ones();
+        if k >= c {
+            k -= c;
+            m >>= width;
+            pos += width;
+        } else {
+            m = low;
+        }
+    }
+    pos
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Self {
+        CardSet(iter.into_iter().fold(0, |m, c| m | (1u64 << c.to_idx())))
+    }
+}
+
+impl<'a> FromIterator<&'a Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = &'a Card>>(iter: I) -> Self {
+        iter.into_iter().copied().collect()
+    }
+}
+
+impl std::ops::BitOr for CardSet {
+    type Output = CardSet;
+
+    fn bitor(self, rhs: CardSet) -> CardSet {
+        self.union(rhs)
+    }
+}
+
+impl std::ops::BitAnd for CardSet {
+    type Output = CardSet;
+
+    fn bitand(self, rhs: CardSet) -> CardSet {
+        self.intersection(rhs)
+    }
+}
+
+impl std::ops::Sub for CardSet {
+    type Output = CardSet;
+
+    fn sub(self, rhs: CardSet) -> CardSet {
+        self.difference(rhs)
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct ValidationError {
     pub error: String,
     pub details: Option<String>,
     pub card: Option<String>,
+    /// 0-based character offset of the problem within `card` (card parse errors only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<usize>,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.details {
+            Some(d) => write!(f, "{}: {}", self.error, d),
+            None => f.write_str(&self.error),
+        }
+    }
 }
 
+impl std::error::Error for ValidationError {}
+
+/// Parse exactly one card; same as `s.parse::<Card>()`.
 pub fn parse_card(s: &str) -> Result<Card, ValidationError> {
-    let t = s.trim().replace("10", "T");
-    if t.len() != 2 {
-        return Err(ValidationError {
-            error: "invalid_card_length".into(),
-            details: Some(format!("Card must be 2 chars (e.g. As, Td), got: {}", s)),
-            card: Some(s.into()),
-        });
+    s.parse()
+}
+
+/// A card parse error in `input` at character `position`.
+fn parse_error(error: &str, details: String, input: &str, position: usize) -> ValidationError {
+    ValidationError {
+        error: error.into(),
+        details: Some(details),
+        card: Some(input.into()),
+        position: Some(position),
     }
-    let rank_ch = t.chars().next().unwrap().to_ascii_uppercase();
-    let suit_ch = t.chars().nth(1).unwrap().to_ascii_lowercase();
-    let rank = RANKS.find(rank_ch).ok_or_else(|| ValidationError {
-        error: "invalid_rank".into(),
-        details: Some(format!("Rank must be one of {}, got: {}", RANKS, rank_ch)),
-        card: Some(s.into()),
-    })? as u8;
-    let suit = SUITS.find(suit_ch).ok_or_else(|| ValidationError {
-        error: "invalid_suit".into(),
-        details: Some(format!("Suit must be s,h,d,c, got: {}", suit_ch)),
-        card: Some(s.into()),
-    })? as u8;
-    Ok(Card { rank, suit })
 }
 
+/// Parse any number of cards, as users paste them: separated by whitespace, commas or
+/// semicolons, or back to back ("AsKd"). Ranks are case-insensitive and accept "10" for
+/// T; suits accept s/h/d/c in either case and the symbols ♠♥♦♣ (also outline ♤♡♢♧).
+/// Errors carry the character offset of the offending rank or suit.
 pub fn parse_cards(s: &str) -> Result<Vec<Card>, ValidationError> {
+    let chars: Vec<char> = s.chars().collect();
     let mut out = Vec::new();
-    for p in s.split_whitespace() {
-        if p.is_empty() {
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() || c == ',' || c == ';' {
+            i += 1;
             continue;
         }
-        out.push(parse_card(p)?);
+        let start = i;
+        let rank = if c == '1' && chars.get(i + 1) == Some(&'0') {
+            i += 2;
+            Rank::Ten
+        } else if let Some(r) = Rank::from_char(c) {
+            i += 1;
+            r
+        } else {
+            let details = format!("Rank must be one of {} or 10, got '{}' at position {}", RANKS, c, i);
+            return Err(parse_error("invalid_rank", details, s, i));
+        };
+        let Some(&suit_ch) = chars.get(i) else {
+            let details = format!("Card at position {} has no suit (e.g. As, Td)", start);
+            return Err(parse_error("invalid_card_length", details, s, i));
+        };
+        let suit = Suit::from_char(suit_ch).ok_or_else(|| {
+            let details = format!("Suit must be s,h,d,c or ♠♥♦♣, got '{}' at position {}", suit_ch, i);
+            parse_error("invalid_suit", details, s, i)
+        })?;
+        i += 1;
+        // Emoji presentation selector pasted after a suit symbol ("♠️").
+        if chars.get(i) == Some(&'\u{FE0F}') {
+            i += 1;
+        }
+        out.push(Card { rank, suit });
     }
     Ok(out)
 }
 
-pub fn card_to_string(c: &Card) -> String {
-    format!(
-        "{}{}",
-        RANKS.chars().nth(c.rank as usize).unwrap(),
-        SUITS.chars().nth(c.suit as usize).unwrap()
-    )
+/// Parse a list of card strings where each entry may hold one or several cards
+/// (["As", "Kd"], ["AsKd"], ["A♠ K♦"]). Error positions are relative to the entry.
+pub fn parse_card_list<S: AsRef<str>>(items: &[S]) -> Result<Vec<Card>, ValidationError> {
+    let mut out = Vec::with_capacity(items.len());
+    for item in items {
+        out.extend(parse_cards(item.as_ref())?);
+    }
+    Ok(out)
 }
 
-pub fn validate_input(hero: &[Card], board: &[Card]) -> Result<(), ValidationError> {
-    if hero.len() != 2 {
+/// Check hero/board sizes for `game` and that hero, board and `dead` (exposed or
+/// mucked cards) share no card.
+pub fn validate_input(game: Game, hero: &[Card], board: &[Card], dead: &[Card]) -> Result<(), ValidationError> {
+    if game.is_stud() {
+        if !(game.min_hero_cards()..=game.hole_cards()).contains(&hero.len()) {
+            return Err(ValidationError {
+                error: "invalid_hero".into(),
+                details: Some(format!(
+                    "hero must be {} to {} cards in {}",
+                    game.min_hero_cards(),
+                    game.hole_cards(),
+                    game.name()
+                )),
+                card: None,
+                position: None,
+            });
+        }
+        if !board.is_empty() {
+            return Err(ValidationError {
+                error: "invalid_board_length".into(),
+                details: Some(format!("{} has no board, got {} cards", game.name(), board.len())),
+                card: None,
+                position: None,
+            });
+        }
+    } else if hero.len() != game.hole_cards() {
         return Err(ValidationError {
             error: "invalid_hero".into(),
-            details: Some("hero must be exactly 2 cards".into()),
+            details: Some(format!("hero must be exactly {} cards", game.hole_cards())),
             card: None,
+            position: None,
         });
     }
     let allowed = [0, 3, 4, 5];
@@ -86,54 +533,97 @@ pub fn validate_input(hero: &[Card], board: &[Card]) -> Result<(), ValidationErr
             error: "invalid_board_length".into(),
             details: Some(format!("board must have 0,3,4,5 cards, got {}", board.len())),
             card: None,
+            position: None,
         });
     }
-    let mut seen = std::collections::HashSet::new();
-    for c in hero.iter().chain(board.iter()) {
-        let idx = c.to_idx();
-        if !seen.insert(idx) {
+    validate_in_deck(game, hero.iter().chain(board.iter()).chain(dead.iter()))?;
+    validate_unique(hero.iter().chain(board.iter()).chain(dead.iter()))
+}
+
+/// Reject cards the game's deck does not contain (2-5 in short deck).
+pub fn validate_in_deck<'a>(game: Game, cards: impl IntoIterator<Item = &'a Card>) -> Result<(), ValidationError> {
+    for c in cards {
+        if c.rank < game.lowest_rank() {
+            return Err(ValidationError {
+                error: "card_not_in_deck".into(),
+                details: Some(format!("{} is not in the {} deck", c, game.name())),
+                card: Some(c.to_string()),
+                position: None,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Reject any card that appears more than once (hero, board, known opponent hands...).
+pub fn validate_unique<'a>(cards: impl IntoIterator<Item = &'a Card>) -> Result<(), ValidationError> {
+    let mut seen = CardSet::EMPTY;
+    for c in cards {
+        if !seen.insert(*c) {
             return Err(ValidationError {
                 error: "duplicate_cards".into(),
-                details: Some(format!("Duplicate card: {}", card_to_string(c))),
-                card: Some(card_to_string(c)),
+                details: Some(format!("Duplicate card: {}", c)),
+                card: Some(c.to_string()),
+                position: None,
             });
         }
     }
     Ok(())
 }
 
-pub fn build_deck(known: &[Card]) -> Vec<usize> {
-    let set: std::collections::HashSet<usize> = known.iter().map(|c| c.to_idx()).collect();
-    (0..52).filter(|i| !set.contains(i)).collect()
+/// Indices of the cards still available for dealing: neither `known` nor `dead`.
+pub fn build_deck(known: &[Card], dead: &[Card]) -> Vec<usize> {
+    build_deck_from(Rank::Two, known, dead)
+}
+
+/// `build_deck` for a deck holding only ranks `lowest_rank..=A` (short deck: 6+).
+pub fn build_deck_from(lowest_rank: Rank, known: &[Card], dead: &[Card]) -> Vec<usize> {
+    let removed: CardSet = known.iter().chain(dead).collect();
+    (CardSet::deck(lowest_rank) - removed).indices().collect()
+}
+
+/// Call `f` on every `k`-card combination of `deck` that avoids the `used` cards, in
+/// lexicographic order. Stops and returns true as soon as `f` returns true. `k` <= 7.
+pub fn any_combination(deck: &[usize], k: usize, used: CardSet, f: &mut impl FnMut(&[usize]) -> bool) -> bool {
+    fn go(
+        deck: &[usize],
+        from: usize,
+        picked: &mut [usize; 7],
+        n: usize,
+        k: usize,
+        used: CardSet,
+        f: &mut impl FnMut(&[usize]) -> bool,
+    ) -> bool {
+        if n == k {
+            return f(&picked[..k]);
+        }
+        for i in from..deck.len() {
+            let c = deck[i];
+            if used.contains_idx(c) {
+                continue;
+            }
+            picked[n] = c;
+            let mut next = used;
+            next.insert_idx(c);
+            if go(deck, i + 1, picked, n + 1, k, next, f) {
+                return true;
+            }
+        }
+        false
+    }
+    go(deck, 0, &mut [0; 7], 0, k, used, f)
 }
 
 /// Partial Fisher–Yates: draw `n` indices from `deck` in place, write drawn indices to `out`.
-pub fn draw_indices(deck: &mut [usize], n: usize, rng: &mut impl FnMut() -> f64, out: &mut [usize]) {
+pub fn draw_indices(deck: &mut [usize], n: usize, rng: &mut impl UniformRng, out: &mut [usize]) {
     for i in 0..n {
-        let j = i + (rng() * (deck.len() - i) as f64) as usize;
+        let j = i + rng.below(deck.len() - i);
         deck.swap(i, j);
         out[i] = deck[i];
     }
 }
 
-/// Seeded RNG (LCG) for reproducibility.
-pub fn seeded_rng(seed: u64) -> impl FnMut() -> f64 {
-    let mut s = seed;
-    move || {
-        s = s.wrapping_mul(1664525).wrapping_add(1013904223);
-        (s >> 32) as f64 / (1u64 << 32) as f64
-    }
-}
-
-trait ToAscii {
-    fn to_ascii_uppercase(&self) -> char;
-    fn to_ascii_lowercase(&self) -> char;
-}
-impl ToAscii for char {
-    fn to_ascii_uppercase(&self) -> char {
-        self.to_uppercase().next().unwrap_or(*self)
-    }
-    fn to_ascii_lowercase(&self) -> char {
-        self.to_lowercase().next().unwrap_or(*self)
-    }
+/// Seeded RNG (default algorithm, stream 0) for reproducibility.
+pub fn seeded_rng(seed: u64) -> SimRng {
+    SimRng::new(RngAlgorithm::default(), seed, 0)
 }
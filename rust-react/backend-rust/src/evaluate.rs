@@ -1,14 +1,32 @@
 // 7-card -> best 5-card hand score (lower = stronger, for comparison).
+// Hot path uses lookup tables generated lazily from the 5-card evaluator.
 
-use crate::cards::Card;
+use crate::cards::{Card, Rank};
+use std::sync::OnceLock;
 
 /// Card index 0-51: suit*13 + rank (rank 0=2, 12=A).
 fn card_idx(c: &Card) -> usize {
     c.to_idx()
 }
 
+/// Which hand ranking a score follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rules {
+    Standard,
+    /// Short-deck (6+): A-6-7-8-9 is the lowest straight and a flush beats a full house.
+    ShortDeck,
+}
+
 /// Evaluate 5 cards (indices 0-51). Lower score = stronger hand.
+/// Score = category (0 = straight flush .. 8 = high card) << 24 | kicker nibbles, so
+/// equal hands score equally and every one of the 7462 hand classes is distinct.
 fn eval5(indices: &[usize; 5]) -> u32 {
+    eval5_rules(indices, Rules::Standard)
+}
+
+/// `eval5` under `rules`. Short-deck scores carry the category's short-deck position in
+/// bits 28.. so they order correctly while bits 24..28 still hold the category.
+fn eval5_rules(indices: &[usize; 5], rules: Rules) -> u32 {
     let mut ranks = [0u8; 5];
     let mut suits = [0u8; 5];
     for (i, &idx) in indices.iter().enumerate() {
@@ -21,13 +39,13 @@ fn eval5(indices: &[usize; 5]) -> u32 {
     let mut rc_len = 0usize;
     let mut r = ranks[0];
     let mut count = 1u8;
-    for i in 1..5 {
-        if ranks[i] == r {
+    for &rk in &ranks[1..] {
+        if rk == r {
             count += 1;
         } else {
             rank_counts[rc_len] = (r, count);
             rc_len += 1;
-            r = ranks[i];
+            r = rk;
             count = 1;
         }
     }
@@ -39,7 +57,10 @@ fn eval5(indices: &[usize; 5]) -> u32 {
         s.sort();
         let a = s[0];
         (s[1] == a + 1 && s[2] == a + 2 && s[3] == a + 3 && s[4] == a + 4)
-            || (s[0] == 0 && s[1] == 1 && s[2] == 2 && s[3] == 3 && s[4] == 12) // wheel A-2-3-4-5
+            || match rules {
+                Rules::Standard => s == [0, 1, 2, 3, 12],  // wheel A-2-3-4-5
+                Rules::ShortDeck => s == [4, 5, 6, 7, 12], // A-6-7-8-9
+            }
     };
     let type_score: u32 = if is_flush && is_straight {
         0
@@ -60,20 +81,39 @@ fn eval5(indices: &[usize; 5]) -> u32 {
     } else {
         8
     };
-    let kicker: u32 = ranks.iter().enumerate().map(|(i, &r)| (r as u32) << (4 * (4 - i))).sum();
-    (type_score << 24) | kicker
+    // Within a category compare grouped ranks (count desc, then rank desc); a higher
+    // rank must give a lower score, so each nibble holds 12 - rank.
+    let kicker: u32 = if is_straight {
+        // ace-low straights are ranked by their second card (5-high wheel, 9-high in short deck)
+        let high = if ranks[0] == 12 && ranks[1] < 11 { ranks[1] } else { ranks[0] };
+        (12 - high as u32) << 16
+    } else {
+        rank_counts[..rc_len]
+            .iter()
+            .enumerate()
+            .map(|(i, &(r, _))| (12 - r as u32) << (4 * (4 - i)))
+            .sum()
+    };
+    let order = match (rules, type_score) {
+        (Rules::Standard, _) => 0,
+        (Rules::ShortDeck, 2) => 3,
+        (Rules::ShortDeck, 3) => 2,
+        (Rules::ShortDeck, t) => t,
+    };
+    (order << 28) | (type_score << 24) | kicker
 }
 
-/// Best 5-card hand from 7 cards (indices 0-51). Lower = stronger. No heap allocation.
-pub fn best_hand_score_7_indices(indices: &[usize; 7]) -> u32 {
+/// Reference 7-card evaluator: best of the 21 five-card subsets via `eval5`.
+/// Slow; used to build and verify the lookup tables and as a benchmark baseline.
+pub fn best_hand_score_7_reference(indices: &[usize; 7]) -> u32 {
     let mut best = u32::MAX;
     let mut c = [0usize; 5];
     for i in 0..7 {
         for j in (i + 1)..7 {
             let mut k = 0;
-            for p in 0..7 {
+            for (p, &idx) in indices.iter().enumerate() {
                 if p != i && p != j {
-                    c[k] = indices[p];
+                    c[k] = idx;
                     k += 1;
                 }
             }
@@ -86,6 +126,170 @@ pub fn best_hand_score_7_indices(indices: &[usize; 7]) -> u32 {
     best
 }
 
+/// 5^rank: a rank multiset (each count <= 4) maps to a unique base-5 key.
+const RANK_POW5: [u32; 13] = {
+    let mut t = [1u32; 13];
+    let mut i = 1;
+    while i < 13 {
+        t[i] = t[i - 1] * 5;
+        i += 1;
+    }
+    t
+};
+
+/// Open-addressing table size for the 73,775 five- to seven-card rank multisets.
+const HASH_BITS: u32 = 17;
+
+/// Lookup tables built once from `eval5`, so scores are identical to the reference.
+struct Tables {
+    /// Best flush/straight-flush score per 13-bit rank mask of one suit (>= 5 bits), else u32::MAX.
+    flush: Vec<u32>,
+    /// Base-5 rank keys (0 = empty slot) and their best non-flush score.
+    keys: Vec<u32>,
+    values: Vec<u32>,
+}
+
+fn slot(key: u32) -> usize {
+    (key.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+}
+
+/// Best `eval5_rules` over all 5-card subsets of `cards` (5..=7 card indices).
+fn best_of_subsets(cards: &[usize], rules: Rules) -> u32 {
+    let n = cards.len();
+    let mut best = u32::MAX;
+    for mask in 0u32..(1 << n) {
+        if mask.count_ones() != 5 {
+            continue;
+        }
+        let mut c = [0usize; 5];
+        let mut k = 0;
+        for (i, &card) in cards.iter().enumerate() {
+            if mask & (1 << i) != 0 {
+                c[k] = card;
+                k += 1;
+            }
+        }
+        best = best.min(eval5_rules(&c, rules));
+    }
+    best
+}
+
+/// Visit every rank-count vector with counts 0..=4 summing to `left`.
+fn for_each_multiset(rank: usize, left: u32, counts: &mut [u32; 13], f: &mut impl FnMut(&[u32; 13])) {
+    if rank == 13 {
+        if left == 0 {
+            f(counts);
+        }
+        return;
+    }
+    for c in 0..=left.min(4) {
+        counts[rank] = c;
+        for_each_multiset(rank + 1, left - c, counts, f);
+    }
+    counts[rank] = 0;
+}
+
+impl Tables {
+    fn build(rules: Rules) -> Self {
+        let mut flush = vec![u32::MAX; 1 << 13];
+        for (mask, entry) in flush.iter_mut().enumerate() {
+            let n = (mask as u32).count_ones();
+            if (5..=7).contains(&n) {
+                let cards: Vec<usize> = (0..13).filter(|r| mask & (1 << r) != 0).collect();
+                *entry = best_of_subsets(&cards, rules);
+            }
+        }
+        let mut keys = vec![0u32; 1 << HASH_BITS];
+        let mut values = vec![0u32; 1 << HASH_BITS];
+        for size in 5..=7 {
+            for_each_multiset(0, size, &mut [0; 13], &mut |counts| {
+                // Spread suits as s,h,d,c,s,... so no 5-card subset is a flush.
+                let mut cards = Vec::with_capacity(7);
+                let mut key = 0u32;
+                for (rank, &c) in counts.iter().enumerate() {
+                    for _ in 0..c {
+                        cards.push((cards.len() % 4) * 13 + rank);
+                        key += RANK_POW5[rank];
+                    }
+                }
+                let mut i = slot(key);
+                while keys[i] != 0 {
+                    i = (i + 1) & ((1 << HASH_BITS) - 1);
+                }
+                keys[i] = key;
+                values[i] = best_of_subsets(&cards, rules);
+            });
+        }
+        Tables { flush, keys, values }
+    }
+
+    fn rank_value(&self, key: u32) -> u32 {
+        let mut i = slot(key);
+        while self.keys[i] != key {
+            i = (i + 1) & ((1 << HASH_BITS) - 1);
+        }
+        self.values[i]
+    }
+}
+
+fn tables(rules: Rules) -> &'static Tables {
+    static STANDARD: OnceLock<Tables> = OnceLock::new();
+    static SHORT_DECK: OnceLock<Tables> = OnceLock::new();
+    match rules {
+        Rules::Standard => STANDARD.get_or_init(|| Tables::build(Rules::Standard)),
+        Rules::ShortDeck => SHORT_DECK.get_or_init(|| Tables::build(Rules::ShortDeck)),
+    }
+}
+
+/// Force table construction (e.g. at startup) so the first request does not pay for it.
+/// Short-deck tables are built on first use.
+pub fn init_tables() {
+    tables(Rules::Standard);
+}
+
+/// Best 5-card hand from 7 cards (indices 0-51). Lower = stronger. No heap allocation.
+/// Table lookup: one hash probe for the rank multiset plus a flush table per suit.
+pub fn best_hand_score_7_indices(indices: &[usize; 7]) -> u32 {
+    best_hand_score_indices(indices)
+}
+
+/// Best 5-card hand from 5 to 7 card indices via the same tables. Lower = stronger;
+/// u32::MAX for other lengths.
+pub fn best_hand_score_indices(indices: &[usize]) -> u32 {
+    best_hand_score_with(Rules::Standard, indices)
+}
+
+/// `best_hand_score_indices` under the given ranking rules.
+pub fn best_hand_score_with(rules: Rules, indices: &[usize]) -> u32 {
+    if !(5..=7).contains(&indices.len()) {
+        return u32::MAX;
+    }
+    let t = tables(rules);
+    let mut key = 0u32;
+    let mut masks = [0u16; 4];
+    for &i in indices {
+        let rank = i % 13;
+        key += RANK_POW5[rank];
+        masks[i / 13] |= 1 << rank;
+    }
+    let mut best = t.rank_value(key);
+    for m in masks {
+        if m.count_ones() >= 5 {
+            best = best.min(t.flush[m as usize]);
+        }
+    }
+    best
+}
+
+/// Best 5-card hand from 5 to 7 cards. Lower = stronger; u32::MAX for other lengths.
+pub fn best_hand_score(cards: &[Card]) -> u32 {
+    match cards.len() {
+        7 => best_hand_score_7(cards),
+        5 | 6 => best_hand_score_indices(&cards.iter().map(card_idx).collect::<Vec<_>>()),
+        _ => u32::MAX,
+    }
+}
+
 /// Best 5-card hand from 7 cards. Lower = stronger. Kept for API compatibility.
 pub fn best_hand_score_7(cards: &[Card]) -> u32 {
     if cards.len() != 7 {
@@ -98,6 +302,324 @@ pub fn best_hand_score_7(cards: &[Card]) -> u32 {
     best_hand_score_7_indices(&idx)
 }
 
+/// Low value of a card for ace-to-five lowball: A = 1, 2 = 2 .. K = 13.
+fn low_value(idx: usize) -> u32 {
+    match idx % 13 {
+        12 => 1,
+        r => r as u32 + 2,
+    }
+}
+
+/// Best 8-or-better low from 5 to 7 card indices (any five): five distinct ranks of
+/// eight or lower, aces low, straights and flushes ignored. Lower = better, compared
+/// from the highest card down; u32::MAX when no low qualifies.
+pub fn best_low8_score_indices(indices: &[usize]) -> u32 {
+    let mut mask = 0u32;
+    for &i in indices {
+        let v = low_value(i);
+        if v <= 8 {
+            mask |= 1 << v;
+        }
+    }
+    if mask.count_ones() < 5 {
+        return u32::MAX;
+    }
+    // The five lowest distinct values make the best low.
+    let mut score = 0u32;
+    let mut taken = 0;
+    for v in 1..=8u32 {
+        if mask & (1 << v) != 0 {
+            score |= v << (4 * taken);
+            taken += 1;
+            if taken == 5 {
+                break;
+            }
+        }
+    }
+    score
+}
+
+/// Best 8-or-better low from 7 cards; see `best_low8_score_indices`.
+pub fn best_low8_score_7_indices(indices: &[usize; 7]) -> u32 {
+    best_low8_score_indices(indices)
+}
+
+/// Ace-to-five lowball score of exactly five cards: aces low, straights and flushes
+/// ignored, pairs bad. The order nibble ranks no pair < one pair < two pair < trips <
+/// full house < quads, the category nibble holds the matching `HandCategory` so
+/// `HandCategory::from_score` still applies. Lower = better.
+fn low_a5_5(five: &[usize]) -> u32 {
+    let mut counts = [0u8; 14];
+    for &i in five {
+        counts[low_value(i) as usize] += 1;
+    }
+    // Groups by (count desc, value desc): the first group decides most.
+    let mut groups = [(0u8, 0u32); 5];
+    let mut n = 0;
+    for v in 1..=13u32 {
+        if counts[v as usize] > 0 {
+            groups[n] = (counts[v as usize], v);
+            n += 1;
+        }
+    }
+    let groups = &mut groups[..n];
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+    let (order, category) = match (groups[0].0, groups.get(1).map_or(0, |g| g.0)) {
+        (1, _) => (0, 8),
+        (2, 1) => (1, 7),
+        (2, 2) => (2, 6),
+        (3, 1) => (3, 5),
+        (3, 2) => (4, 2),
+        _ => (5, 1),
+    };
+    let kickers = groups.iter().fold(0u32, |acc, &(_, v)| (acc << 4) | v) << (4 * (5 - groups.len()));
+    (order << 28) | (category << 24) | kickers
+}
+
+/// Best ace-to-five low (razz) from 5 to 7 card indices. Lower = better; every hand
+/// has a low, a pair only counts when five distinct ranks are not available.
+pub fn best_low_a5_score_indices(indices: &[usize]) -> u32 {
+    let mask = indices.iter().fold(0u32, |m, &i| m | (1 << low_value(i)));
+    if mask.count_ones() >= 5 {
+        // The five lowest distinct values: no pair, packed like `low_a5_5` does.
+        let mut score = 0u32;
+        let mut taken = 0;
+        for v in 1..=13u32 {
+            if mask & (1 << v) != 0 {
+                score |= v << (4 * taken);
+                taken += 1;
+                if taken == 5 {
+                    break;
+                }
+            }
+        }
+        return (8 << 24) | score;
+    }
+    let n = indices.len();
+    let mut best = u32::MAX;
+    for a in 0..n {
+        for b in a + 1..n {
+            for c in b + 1..n {
+                for d in c + 1..n {
+                    for e in d + 1..n {
+                        let five = [indices[a], indices[b], indices[c], indices[d], indices[e]];
+                        best = best.min(low_a5_5(&five));
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Hand category, declared weakest first so `Ord` means "stronger is greater".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandCategory {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+impl HandCategory {
+    /// All categories, strongest first.
+    pub const ALL: [HandCategory; 9] = [
+        HandCategory::StraightFlush,
+        HandCategory::FourOfAKind,
+        HandCategory::FullHouse,
+        HandCategory::Flush,
+        HandCategory::Straight,
+        HandCategory::ThreeOfAKind,
+        HandCategory::TwoPair,
+        HandCategory::OnePair,
+        HandCategory::HighCard,
+    ];
+
+    /// Category of a score from this module (lower score = stronger).
+    pub fn from_score(score: u32) -> HandCategory {
+        Self::ALL[(((score >> 24) & 0xF) as usize).min(8)]
+    }
+
+    /// Strength of a score's category under the rules it was scored with, lower = stronger.
+    /// Unlike the order of `HandCategory` itself, a short-deck flush beats a full house.
+    pub fn strength(score: u32) -> u32 {
+        score >> 24
+    }
+
+    /// Position in `ALL` (0 = straight flush .. 8 = high card).
+    pub fn index(self) -> usize {
+        8 - self as usize
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HandCategory::StraightFlush => "Straight Flush",
+            HandCategory::FourOfAKind => "Four of a Kind",
+            HandCategory::FullHouse => "Full House",
+            HandCategory::Flush => "Flush",
+            HandCategory::Straight => "Straight",
+            HandCategory::ThreeOfAKind => "Three of a Kind",
+            HandCategory::TwoPair => "Two Pair",
+            HandCategory::OnePair => "One Pair",
+            HandCategory::HighCard => "High Card",
+        }
+    }
+}
+
+const RANK_NAMES: [&str; 13] = [
+    "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack", "Queen", "King", "Ace",
+];
+const RANK_PLURALS: [&str; 13] = [
+    "Twos", "Threes", "Fours", "Fives", "Sixes", "Sevens", "Eights", "Nines", "Tens", "Jacks", "Queens",
+    "Kings", "Aces",
+];
+
+/// A made 5-card hand: its score and the five cards used, in display order
+/// (grouped by count then rank; straights high to low, wheel as 5-4-3-2-A).
+#[derive(Debug, Clone, Copy)]
+pub struct HandRank {
+    score: u32,
+    cards: [Card; 5],
+}
+
+impl HandRank {
+    /// Raw score (lower = stronger), comparable with `best_hand_score_7*`.
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn category(&self) -> HandCategory {
+        HandCategory::from_score(self.score)
+    }
+
+    pub fn cards(&self) -> &[Card; 5] {
+        &self.cards
+    }
+
+    fn rank_at(&self, i: usize) -> usize {
+        self.cards[i].rank as usize
+    }
+
+    fn kickers(&self, from: usize) -> String {
+        let names: Vec<&str> = (from..5).map(|i| RANK_NAMES[self.rank_at(i)]).collect();
+        match names.len() {
+            0 => String::new(),
+            1 => format!(", {} kicker", names[0]),
+            _ => format!(", {} kickers", names.join("-")),
+        }
+    }
+}
+
+impl PartialEq for HandRank {
+    fn eq(&self, other: &Self) -> bool {
+        self.score == other.score
+    }
+}
+
+impl Eq for HandRank {}
+
+impl PartialOrd for HandRank {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Stronger hands compare greater.
+impl Ord for HandRank {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.score.cmp(&self.score)
+    }
+}
+
+impl std::fmt::Display for HandRank {
+    /// e.g. "Two Pair, Kings and Sevens, Ace kicker".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cat = self.category();
+        let r = |i: usize| self.rank_at(i);
+        match cat {
+            HandCategory::StraightFlush if r(0) == 12 => write!(f, "Royal Flush"),
+            HandCategory::StraightFlush | HandCategory::Straight | HandCategory::Flush => {
+                write!(f, "{}, {} high", cat.name(), RANK_NAMES[r(0)])
+            }
+            HandCategory::FourOfAKind => write!(f, "{}, {}{}", cat.name(), RANK_PLURALS[r(0)], self.kickers(4)),
+            HandCategory::FullHouse => write!(
+                f,
+                "{}, {} full of {}",
+                cat.name(),
+                RANK_PLURALS[r(0)],
+                RANK_PLURALS[r(3)]
+            ),
+            HandCategory::ThreeOfAKind => write!(f, "{}, {}{}", cat.name(), RANK_PLURALS[r(0)], self.kickers(3)),
+            HandCategory::TwoPair => write!(
+                f,
+                "{}, {} and {}{}",
+                cat.name(),
+                RANK_PLURALS[r(0)],
+                RANK_PLURALS[r(2)],
+                self.kickers(4)
+            ),
+            HandCategory::OnePair => write!(f, "Pair of {}{}", RANK_PLURALS[r(0)], self.kickers(2)),
+            HandCategory::HighCard => write!(f, "{} high{}", RANK_NAMES[r(0)], self.kickers(1)),
+        }
+    }
+}
+
+/// Put five cards in display order for their category.
+fn display_order(mut cards: [Card; 5]) -> [Card; 5] {
+    let mut counts = [0u8; 13];
+    for c in &cards {
+        counts[c.rank as usize] += 1;
+    }
+    cards.sort_by(|a, b| {
+        counts[b.rank as usize]
+            .cmp(&counts[a.rank as usize])
+            .then(b.rank.cmp(&a.rank))
+            .then(a.suit.cmp(&b.suit))
+    });
+    // wheel: A-5-4-3-2 -> 5-4-3-2-A
+    let ranks: Vec<Rank> = cards.iter().map(|c| c.rank).collect();
+    if ranks == [Rank::Ace, Rank::Five, Rank::Four, Rank::Three, Rank::Two] {
+        cards.rotate_left(1);
+    }
+    cards
+}
+
+/// Evaluate 5 to 7 cards into their best 5-card `HandRank`. None for other lengths.
+pub fn evaluate_hand(cards: &[Card]) -> Option<HandRank> {
+    if !(5..=7).contains(&cards.len()) {
+        return None;
+    }
+    let n = cards.len();
+    let mut best: Option<(u32, [Card; 5])> = None;
+    for mask in 0u32..(1 << n) {
+        if mask.count_ones() != 5 {
+            continue;
+        }
+        let mut five = [cards[0]; 5];
+        let mut k = 0;
+        for (i, c) in cards.iter().enumerate() {
+            if mask & (1 << i) != 0 {
+                five[k] = *c;
+                k += 1;
+            }
+        }
+        let score = eval5(&five.map(|c| card_idx(&c)));
+        if best.is_none_or(|(b, _)| score < b) {
+            best = Some((score, five));
+        }
+    }
+    best.map(|(score, five)| HandRank {
+        score,
+        cards: display_order(five),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,4 +637,187 @@ mod tests {
             .collect();
         assert!(best_hand_score_7(&royal) < best_hand_score_7(&high));
     }
+
+    #[test]
+    fn test_hand_rank_description() {
+        let d = |s: &str| evaluate_hand(&crate::cards::parse_cards(s).unwrap()).unwrap();
+        assert_eq!(d("Kh Kd 7c 7s Ah 2c 3d").to_string(), "Two Pair, Kings and Sevens, Ace kicker");
+        assert_eq!(d("As Ks Qs Js Ts 2c 3d").to_string(), "Royal Flush");
+        assert_eq!(d("Ah 2d 3c 4s 5h Kd").to_string(), "Straight, Five high");
+        assert_eq!(d("Th Td Tc 7s 7h").to_string(), "Full House, Tens full of Sevens");
+        assert_eq!(d("9h 9d Ac Ks 4h").to_string(), "Pair of Nines, Ace-King-Four kickers");
+        assert_eq!(d("Ah Jd 8c 6s 2h").to_string(), "Ace high, Jack-Eight-Six-Two kickers");
+        let wheel = d("Ah 2d 3c 4s 5h");
+        assert_eq!(wheel.cards()[4].to_string(), "Ah");
+        assert_eq!(d("Kh Kd 7c 7s Ah").category(), HandCategory::TwoPair);
+        assert!(d("Th Td Tc 7s 7h") > d("Ah Kh 9h 5h 3h"));
+        assert_eq!(d("Kh Kd 7c 7s Ah 2c 3d").score(), {
+            let c = crate::cards::parse_cards("Kh Kd 7c 7s Ah 2c 3d").unwrap();
+            best_hand_score_7(&c)
+        });
+    }
+
+    #[test]
+    fn test_all_5_card_equivalence_classes() {
+        use std::collections::{BTreeMap, HashSet};
+        let mut distinct = HashSet::new();
+        for a in 0..52 {
+            for b in a + 1..52 {
+                for c in b + 1..52 {
+                    for d in c + 1..52 {
+                        for e in d + 1..52 {
+                            distinct.insert(eval5(&[a, b, c, d, e]));
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(distinct.len(), 7462);
+        let mut per_category: BTreeMap<u32, usize> = BTreeMap::new();
+        for s in &distinct {
+            *per_category.entry(s >> 24).or_default() += 1;
+        }
+        let expected = [10, 156, 156, 1277, 10, 858, 858, 2860, 1277];
+        assert_eq!(per_category.values().copied().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_eval5_ordering() {
+        let e = |s: &str| {
+            let c = crate::cards::parse_cards(s).unwrap();
+            eval5(&[c[0].to_idx(), c[1].to_idx(), c[2].to_idx(), c[3].to_idx(), c[4].to_idx()])
+        };
+        // pair of kings beats pair of twos with an ace kicker
+        assert!(e("Kh Kd 3c 4s 5h") < e("2h 2d Ac Ks Qh"));
+        // wheel is a straight, but the lowest one
+        assert!(e("Ah 2d 3c 4s 5h") < e("Ah Ad Kc Qs Jh"));
+        assert!(e("2h 3d 4c 5s 6h") < e("Ah 2d 3c 4s 5h"));
+        assert!(e("5s 4s 3s 2s As") < e("Kh Kd Kc Ks 2h"));
+        // two pair: top pair first, then second pair, then kicker
+        assert!(e("Kh Kd 2c 2s 3h") < e("Qh Qd Jc Js Ah"));
+        assert!(e("Kh Kd 3c 3s 2h") < e("Kc Ks 2c 2d Ah"));
+        // full house: trips rank dominates
+        assert!(e("3h 3d 3c 2s 2h") < e("2c 2d 2s Ah Ad"));
+        // flush kickers go all the way down
+        assert_eq!(e("Ah Kh 9h 5h 3h"), e("As Ks 9s 5s 3s"));
+        assert!(e("Ah Kh 9h 5h 3h") < e("Ah Kh 9h 5h 2h"));
+    }
+
+    #[test]
+    fn test_short_deck_ranking() {
+        let e = |s: &str| {
+            let idx: Vec<usize> = crate::cards::parse_cards(s).unwrap().iter().map(|c| c.to_idx()).collect();
+            best_hand_score_with(Rules::ShortDeck, &idx)
+        };
+        // flush beats full house
+        assert!(e("Ah Th 8h 7h 6h") < e("Kc Kd Ks 6c 6d"));
+        assert_eq!(HandCategory::from_score(e("Ah Th 8h 7h 6h")), HandCategory::Flush);
+        assert_eq!(HandCategory::from_score(e("Kc Kd Ks 6c 6d")), HandCategory::FullHouse);
+        // A-6-7-8-9 is the lowest straight, still above trips
+        assert_eq!(HandCategory::from_score(e("Ah 6d 7c 8s 9h")), HandCategory::Straight);
+        assert!(e("6d 7c 8s 9h Th") < e("Ah 6d 7c 8s 9h"));
+        assert!(e("Ah 6d 7c 8s 9h") < e("Qc Qd Qs 6c 7d"));
+        assert_eq!(HandCategory::from_score(e("As 6s 7s 8s 9s")), HandCategory::StraightFlush);
+    }
+
+    #[test]
+    fn test_low8() {
+        let low = |s: &str| {
+            let idx: Vec<usize> = crate::cards::parse_cards(s).unwrap().iter().map(|c| c.to_idx()).collect();
+            best_low8_score_indices(&idx)
+        };
+        // the wheel is the best low; straights and flushes do not count against it
+        assert!(low("Ah 2h 3h 4h 5h") < low("Ac 2d 3c 4d 6s"));
+        // compare from the top card down: 7-5-4-3-2 beats 7-6-3-2-A
+        assert!(low("7c 5d 4c 3s 2h") < low("7d 6s 3d 2c As"));
+        // pairs are skipped; the five lowest distinct ranks play
+        assert_eq!(low("Ac Ad 2c 3s 4h 8d Kc"), low("As 2d 3c 4s 8h"));
+        // nine-high and four distinct low ranks do not qualify
+        assert_eq!(low("9c 5d 4c 3s 2h"), u32::MAX);
+        assert_eq!(low("Ac 2d 3c 3s 4h Kd Qc"), u32::MAX);
+    }
+
+    #[test]
+    fn test_razz_low() {
+        let low = |s: &str| {
+            let idx: Vec<usize> = crate::cards::parse_cards(s).unwrap().iter().map(|c| c.to_idx()).collect();
+            best_low_a5_score_indices(&idx)
+        };
+        // the wheel is the nuts, straight and flush included
+        assert_eq!(low("Ah 2h 3h 4h 5h Kc Kd"), low("As 2d 3c 4s 5h"));
+        assert!(low("Ah 2h 3h 4h 5h") < low("Ac 2d 3c 4d 6s"));
+        // K-high no pair beats any pair
+        assert!(low("Kc Qd Jc 9s 8h") < low("Ac Ad 2c 3s 4h"));
+        // with four distinct ranks the lowest pair plays: A-A-2-3-4 beats 2-2-A-3-4
+        assert!(low("Ac Ad 2c 3s 4h 4d 3d") < low("2c 2d Ac 3s 4h 4d 3d"));
+        assert_eq!(HandCategory::from_score(low("Ac Ad 2c 3s 4h 4d 3d")), HandCategory::OnePair);
+        assert_eq!(HandCategory::from_score(low("9c 8d 7c 6s 5h")), HandCategory::HighCard);
+        // two pair loses to one pair, trips to two pair
+        assert!(low("Kc Kd Qc Js Th") < low("Ac Ad 2c 2s 3h"));
+        assert!(low("Ac Ad 2c 2s 3h") < low("Ac Ad Ah 2s 3h"));
+        // the no-pair shortcut agrees with scoring every five-card subset
+        let mut rng = crate::cards::seeded_rng(7);
+        for _ in 0..2_000 {
+            let mut deck: Vec<usize> = (0..52).collect();
+            let mut idx = [0usize; 7];
+            crate::cards::draw_indices(&mut deck, 7, &mut rng, &mut idx);
+            let mut brute = u32::MAX;
+            for a in 0..7 {
+                for b in a + 1..7 {
+                    let five: Vec<usize> = (0..7).filter(|&i| i != a && i != b).map(|i| idx[i]).collect();
+                    brute = brute.min(low_a5_5(&five));
+                }
+            }
+            assert_eq!(best_low_a5_score_indices(&idx), brute, "{:?}", idx);
+        }
+    }
+
+    #[test]
+    fn test_razz_made_hand_categories() {
+        let low = |s: &str| {
+            let idx: Vec<usize> = crate::cards::parse_cards(s).unwrap().iter().map(|c| c.to_idx()).collect();
+            best_low_a5_score_indices(&idx)
+        };
+        assert_eq!(HandCategory::from_score(low("Ac Ad Ah As 2c 2d 2h")), HandCategory::FullHouse);
+        assert_eq!(HandCategory::from_score(low("Ac Ad Ah 2c 2d 2h 2s")), HandCategory::FullHouse);
+        assert_eq!(HandCategory::from_score(low("Ac Ad Ah 2c 3d")), HandCategory::ThreeOfAKind);
+        assert_eq!(HandCategory::from_score(low("Ac Ad Ah As 2c")), HandCategory::FourOfAKind);
+        assert!(low("Ac Ad Ah 2c 3d") < low("Ac Ad Ah 2c 2d"));
+    }
+
+    #[test]
+    fn test_tables_match_reference() {
+        let mut rng = crate::cards::seeded_rng(42);
+        for _ in 0..20_000 {
+            let mut deck: Vec<usize> = (0..52).collect();
+            let mut idx = [0usize; 7];
+            crate::cards::draw_indices(&mut deck, 7, &mut rng, &mut idx);
+            assert_eq!(
+                best_hand_score_7_indices(&idx),
+                best_hand_score_7_reference(&idx),
+                "{:?}",
+                idx
+            );
+            for n in [5, 6] {
+                assert_eq!(best_hand_score_indices(&idx[..n]), best_of_subsets(&idx[..n], Rules::Standard));
+            }
+            assert_eq!(
+                best_hand_score_with(Rules::ShortDeck, &idx),
+                best_of_subsets(&idx, Rules::ShortDeck)
+            );
+        }
+    }
+
+    #[test]
+    fn test_category_strength_follows_rules() {
+        let idx = |s: &str| -> Vec<usize> {
+            crate::cards::parse_cards(s).unwrap().iter().map(|c| c.to_idx()).collect()
+        };
+        let (flush, boat) = (idx("As 9s 7s 6s Ks"), idx("Kc Kd Kh 7c 7d"));
+        let strength = |rules, cards: &[usize]| HandCategory::strength(best_hand_score_with(rules, cards));
+        assert!(strength(Rules::Standard, &boat) < strength(Rules::Standard, &flush));
+        assert!(strength(Rules::ShortDeck, &flush) < strength(Rules::ShortDeck, &boat));
+        let pair = idx("Ac Ad 7s 8h 9c");
+        assert!(strength(Rules::ShortDeck, &boat) < strength(Rules::ShortDeck, &pair));
+    }
 }
@@ -0,0 +1,237 @@
+// Exhaustive enumeration: every remaining runout and every opponent holding.
+// Used instead of Monte Carlo when the state space is small (turn/river, flop heads-up).
+
+use crate::cards::{any_combination, CardSet};
+use crate::game::Game;
+use crate::simulate::{self, Deal, Opponent, SimOptions, SimResult, Tally};
+use rayon::prelude::*;
+use std::time::Instant;
+
+/// Default maximum number of (runout × opponent holdings) states to enumerate.
+pub const DEFAULT_EXACT_BUDGET: u64 = 2_000_000;
+
+fn choose(n: u64, k: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1u64, |acc, i| acc.saturating_mul(n - i) / (i + 1))
+}
+
+/// Upper bound on the number of states `enumerate` would visit (saturating).
+/// Range sizes are counted before card-removal between opponents.
+pub fn state_count(deal: &Deal) -> u64 {
+    let known = deal.known_cards();
+    let hole = deal.game.hole_cards();
+    let mut remaining = (deal.game.deck_size() - known.len()) as u64;
+    // Ways to deal `k` more cards, which then leave the deck.
+    let mut deal_unknown = |k: usize| {
+        let n = choose(remaining, k as u64);
+        remaining = remaining.saturating_sub(k as u64);
+        n
+    };
+    let mut count = deal_unknown(deal.board_unknown_cards()).saturating_mul(deal_unknown(deal.hero_unknown_cards()));
+    for o in &deal.opponents {
+        let n = match o {
+            Opponent::Range(r) => r.len() as u64,
+            other => deal_unknown(other.unknown_cards(hole)),
+        };
+        count = count.saturating_mul(n);
+    }
+    count
+}
+
+/// All `k`-subsets of `items`, each written as a full 5-card board after `fixed`.
+fn runouts(fixed: &[usize], items: &[usize], k: usize) -> Vec<[usize; 5]> {
+    let mut out = Vec::new();
+    let mut idx: Vec<usize> = (0..k).collect();
+    let n = items.len();
+    if k > n {
+        return out;
+    }
+    loop {
+        let mut b = [0usize; 5];
+        b[..fixed.len()].copy_from_slice(fixed);
+        for (i, &j) in idx.iter().enumerate() {
+            b[fixed.len() + i] = items[j];
+        }
+        out.push(b);
+        // advance to the next combination in lexicographic order
+        let mut i = k;
+        while i > 0 && idx[i - 1] == n - k + i - 1 {
+            i -= 1;
+        }
+        if i == 0 {
+            return out;
+        }
+        idx[i - 1] += 1;
+        for j in i..k {
+            idx[j] = idx[j - 1] + 1;
+        }
+    }
+}
+
+struct Walker<'a> {
+    game: Game,
+    opponents: &'a [Opponent],
+    /// Card indices of each `Opponent::Known` seat (empty for other seats).
+    known_holes: &'a [Vec<usize>],
+    deck: &'a [usize],
+    board: &'a [usize],
+    scores: Vec<u32>,
+    /// 8-or-better lows, filled only in hi-lo games.
+    lows: Vec<u32>,
+}
+
+impl Walker<'_> {
+    /// Assign holdings to `opponents[seat..]`, then record the showdown.
+    fn walk(&mut self, seat: usize, used: CardSet, weight: f64, tally: &mut Tally) {
+        if seat == self.opponents.len() {
+            if self.game.is_hi_lo() {
+                tally.record_hilo(&self.scores, &self.lows, weight);
+            } else {
+                tally.record(&self.scores, weight);
+            }
+            return;
+        }
+        match &self.opponents[seat] {
+            Opponent::Range(r) => {
+                for (hole, w) in r.weighted() {
+                    let m = CardSet::from_indices(&hole);
+                    if !used.is_disjoint(m) || w <= 0.0 {
+                        continue;
+                    }
+                    self.score(seat, &hole);
+                    self.walk(seat + 1, used | m, weight * w, tally);
+                }
+            }
+            // Random seats and known seats with cards still to come (stud).
+            other => {
+                let (deck, known_holes) = (self.deck, self.known_holes);
+                let known = &known_holes[seat];
+                let k = self.game.hole_cards();
+                let missing = other.unknown_cards(k);
+                if missing == 0 {
+                    self.score(seat, known);
+                    self.walk(seat + 1, used, weight, tally);
+                    return;
+                }
+                let mut hand = [0usize; 7];
+                hand[..known.len()].copy_from_slice(known);
+                any_combination(deck, missing, used, &mut |rest| {
+                    let m = used | CardSet::from_indices(rest);
+                    hand[known.len()..k].copy_from_slice(rest);
+                    self.score(seat, &hand[..k]);
+                    self.walk(seat + 1, m, weight, tally);
+                    false
+                });
+            }
+        }
+    }
+
+    fn score(&mut self, seat: usize, hole: &[usize]) {
+        self.scores[seat + 1] = self.game.score(hole, self.board);
+        if self.game.is_hi_lo() {
+            self.lows[seat + 1] = self.game.low_score(hole, self.board);
+        }
+    }
+}
+
+/// Exact equity over every runout and opponent holding (range combos weighted).
+/// `trials` in the result is the number of boards (in stud, hero completions) enumerated,
+/// or 0 when no opponent holding fits the ranges (nothing was recorded).
+pub fn enumerate(deal: &Deal) -> SimResult {
+    let start = Instant::now();
+    let known = deal.known_cards();
+    let opponents = deal.opponents_without(&known);
+    let deck = deal.game.deck(&known);
+    let fixed: Vec<usize> = deal.board.iter().map(|c| c.to_idx()).collect();
+    // A runout is the rest of the board, or in stud the rest of hero's hand; stud games
+    // have no board and a complete hand otherwise, so at most one of them is missing.
+    let hero_missing = deal.hero_unknown_cards();
+    let (boards, dealt) = if hero_missing > 0 {
+        (runouts(&[], &deck, hero_missing), hero_missing)
+    } else {
+        (runouts(&fixed, &deck, deal.board_unknown_cards()), deal.game.board_cards())
+    };
+    let num_seats = opponents.len() + 1;
+    let hero_known: Vec<usize> = deal.hero.iter().map(|c| c.to_idx()).collect();
+    let known_holes: Vec<Vec<usize>> = opponents
+        .iter()
+        .map(|o| match o {
+            Opponent::Known(h) => h.iter().map(|c| c.to_idx()).collect(),
+            _ => Vec::new(),
+        })
+        .collect();
+    let tally = boards
+        .par_iter()
+        .fold(
+            || Tally::new(num_seats),
+            |mut tally, b| {
+                let board_mask = CardSet::from_indices(&b[..dealt]);
+                let rest: Vec<usize> = deck.iter().copied().filter(|&c| !board_mask.contains_idx(c)).collect();
+                let hero: Vec<usize> = hero_known.iter().chain(&b[..hero_missing]).copied().collect();
+                let board = &b[..deal.game.board_cards()];
+                let mut w = Walker {
+                    game: deal.game,
+                    opponents: &opponents,
+                    known_holes: &known_holes,
+                    deck: &rest,
+                    board,
+                    scores: vec![0; num_seats],
+                    lows: vec![u32::MAX; num_seats],
+                };
+                w.scores[0] = deal.game.score(&hero, board);
+                w.lows[0] = deal.game.low_score(&hero, board);
+                w.walk(0, board_mask, 1.0, &mut tally);
+                tally
+            },
+        )
+        .reduce(|| Tally::new(num_seats), Tally::merge);
+    let trials = if tally.total() > 0.0 { boards.len() as u64 } else { 0 };
+    tally.into_result(deal.game, trials, start.elapsed().as_millis() as u64, false)
+}
+
+/// Exact enumeration when the state count fits `budget` (0 disables), else Monte Carlo
+/// with `trials`. Returns the result and the method name ("exact" or "monte_carlo").
+pub fn equity_auto(
+    deal: &Deal,
+    budget: u64,
+    trials: u64,
+    options: impl Into<SimOptions>,
+) -> (SimResult, &'static str) {
+    if budget > 0 && state_count(deal) <= budget {
+        (enumerate(deal), "exact")
+    } else {
+        (simulate::simulate_deal(deal, trials, options), "monte_carlo")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse_cards;
+
+    #[test]
+    fn test_state_count() {
+        let hero = parse_cards("As Kd").unwrap();
+        let board = parse_cards("Qs 7d 2c Jh").unwrap();
+        assert_eq!(state_count(&Deal::holdem(&hero, &board, &[Opponent::Random])), 46 * 990);
+    }
+
+    #[test]
+    fn test_enumerate_river_known() {
+        let hero = parse_cards("As Ks").unwrap();
+        let board = parse_cards("Qs Js Ts 2c 3d").unwrap();
+        let opp = parse_cards("Ah Ad").unwrap();
+        let r = enumerate(&Deal::holdem(&hero, &board, &[Opponent::Known(opp)]));
+        assert_eq!(r.trials, 1);
+        assert_eq!(r.win, 1.0);
+    }
+
+    #[test]
+    fn test_runouts() {
+        assert_eq!(runouts(&[], &[0, 1, 2, 3, 4, 5], 5).len(), 6);
+        assert_eq!(runouts(&[9, 8, 7], &[0, 1, 2, 3], 2).len(), 6);
+        assert_eq!(runouts(&[9, 8, 7, 6, 5], &[0, 1], 0).len(), 1);
+    }
+}
@@ -0,0 +1,278 @@
+// Game variants: deck, how many hole cards each player gets and how a hand is scored.
+// Hold'em uses the best 5 of hole + board; Omaha must use exactly two hole cards
+// and exactly three board cards. Short deck is Hold'em with 6 through A and its own
+// hand ranking. Hi-lo games also score an 8-or-better low for half the pot.
+// Stud games have no board: each player ends with seven cards of their own.
+
+use crate::cards::{build_deck_from, Card, Rank, ValidationError};
+use crate::evaluate::{
+    best_hand_score_7_indices, best_hand_score_indices, best_hand_score_with, best_low8_score_indices,
+    best_low_a5_score_indices, Rules,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Game {
+    #[default]
+    Holdem,
+    /// Pot-Limit Omaha, 4 hole cards.
+    Plo4,
+    /// Pot-Limit Omaha, 5 hole cards.
+    Plo5,
+    /// Short-deck (6+) Hold'em: 36 cards, flush beats full house, A-6-7-8-9 straight.
+    ShortDeck,
+    /// Omaha Hi/Lo 8-or-better, 4 hole cards.
+    Plo4HiLo,
+    /// Omaha Hi/Lo 8-or-better, 5 hole cards.
+    Plo5HiLo,
+    /// Seven-card stud, high hand wins.
+    Stud,
+    /// Seven-card stud Hi/Lo 8-or-better.
+    Stud8,
+    /// Razz: seven-card stud, ace-to-five low wins.
+    Razz,
+}
+
+/// Best `eval` over every 2-hole-card + 3-board-card five (the Omaha rule).
+fn omaha_best(hole: &[usize], board: &[usize], eval: impl Fn(&[usize]) -> u32) -> u32 {
+    let mut best = u32::MAX;
+    for i in 0..hole.len() {
+        for j in i + 1..hole.len() {
+            for x in 0..board.len() {
+                for y in x + 1..board.len() {
+                    for z in y + 1..board.len() {
+                        best = best.min(eval(&[hole[i], hole[j], board[x], board[y], board[z]]));
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Best hand from hole + board (5 to 7 cards in all) under `rules`, gathered on the stack
+/// so scoring allocates nothing.
+fn hold_em_best(rules: Rules, hole: &[usize], board: &[usize]) -> u32 {
+    let n = hole.len() + board.len();
+    if n > 7 {
+        return u32::MAX;
+    }
+    let mut cards = [0usize; 7];
+    cards[..hole.len()].copy_from_slice(hole);
+    cards[hole.len()..n].copy_from_slice(board);
+    best_hand_score_with(rules, &cards[..n])
+}
+
+impl Game {
+    pub const ALL: [Game; 9] = [
+        Game::Holdem,
+        Game::Plo4,
+        Game::Plo5,
+        Game::ShortDeck,
+        Game::Plo4HiLo,
+        Game::Plo5HiLo,
+        Game::Stud,
+        Game::Stud8,
+        Game::Razz,
+    ];
+
+    /// Parse "holdem", "plo4" (or "omaha", "plo"), "plo5", "short_deck" (or "6plus"),
+    /// "plo4_hilo" (or "o8", "plo8"), "plo5_hilo", "stud", "stud8" (or "stud_hilo")
+    /// and "razz", case-insensitive.
+    pub fn parse(s: &str) -> Result<Self, ValidationError> {
+        match s.trim().to_lowercase().as_str() {
+            "holdem" | "nlhe" => Ok(Game::Holdem),
+            "plo4" | "plo" | "omaha" => Ok(Game::Plo4),
+            "plo5" => Ok(Game::Plo5),
+            "short_deck" | "shortdeck" | "6plus" | "6+" => Ok(Game::ShortDeck),
+            "plo4_hilo" | "o8" | "plo8" => Ok(Game::Plo4HiLo),
+            "plo5_hilo" => Ok(Game::Plo5HiLo),
+            "stud" | "stud7" => Ok(Game::Stud),
+            "stud8" | "stud_hilo" => Ok(Game::Stud8),
+            "razz" => Ok(Game::Razz),
+            _ => Err(ValidationError {
+                error: "invalid_game".into(),
+                details: Some(format!(
+                    "game must be one of {}, got: {}",
+                    Game::ALL.map(|g| g.name()).join(", "),
+                    s
+                )),
+                card: None,
+                position: None,
+            }),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Game::Holdem => "holdem",
+            Game::Plo4 => "plo4",
+            Game::Plo5 => "plo5",
+            Game::ShortDeck => "short_deck",
+            Game::Plo4HiLo => "plo4_hilo",
+            Game::Plo5HiLo => "plo5_hilo",
+            Game::Stud => "stud",
+            Game::Stud8 => "stud8",
+            Game::Razz => "razz",
+        }
+    }
+
+    /// Cards each player holds at showdown, not counting the board (seven in stud).
+    pub fn hole_cards(&self) -> usize {
+        match self {
+            Game::Holdem | Game::ShortDeck => 2,
+            Game::Plo4 | Game::Plo4HiLo => 4,
+            Game::Plo5 | Game::Plo5HiLo => 5,
+            Game::Stud | Game::Stud8 | Game::Razz => 7,
+        }
+    }
+
+    /// Shared board cards at showdown: 5, or 0 in stud games.
+    pub fn board_cards(&self) -> usize {
+        if self.is_stud() {
+            0
+        } else {
+            5
+        }
+    }
+
+    /// Stud games: no board, and players' hands may be only partly known (the up cards).
+    pub fn is_stud(&self) -> bool {
+        matches!(self, Game::Stud | Game::Stud8 | Game::Razz)
+    }
+
+    /// Fewest cards hero must give: the whole hand, or the three of third street in stud.
+    pub fn min_hero_cards(&self) -> usize {
+        if self.is_stud() {
+            3
+        } else {
+            self.hole_cards()
+        }
+    }
+
+    /// True when half the pot goes to the best 8-or-better low.
+    pub fn is_hi_lo(&self) -> bool {
+        matches!(self, Game::Plo4HiLo | Game::Plo5HiLo | Game::Stud8)
+    }
+
+    /// Lowest rank in the deck (short deck starts at six).
+    pub fn lowest_rank(&self) -> Rank {
+        match self {
+            Game::ShortDeck => Rank::Six,
+            _ => Rank::Two,
+        }
+    }
+
+    pub fn deck_size(&self) -> usize {
+        4 * (13 - self.lowest_rank() as usize)
+    }
+
+    /// Indices of the game's cards that are not in `known`.
+    pub fn deck(&self, known: &[Card]) -> Vec<usize> {
+        build_deck_from(self.lowest_rank(), known, &[])
+    }
+
+    /// Cards of a full 52-card deck this game leaves out (used to trim hand ranges).
+    pub fn missing_cards(&self) -> Vec<Card> {
+        (0..52)
+            .map(Card::from_idx)
+            .filter(|c| c.rank < self.lowest_rank())
+            .collect()
+    }
+
+    /// Best score for `hole` on a 3- to 5-card `board` (card indices; a full seven-card
+    /// hand and no board in stud). Lower = stronger; in razz the best low is strongest.
+    pub fn score(&self, hole: &[usize], board: &[usize]) -> u32 {
+        match self {
+            Game::Holdem => {
+                if let ([a, b], [c, d, e, f, g]) = (hole, board) {
+                    return best_hand_score_7_indices(&[*a, *b, *c, *d, *e, *f, *g]);
+                }
+                hold_em_best(Rules::Standard, hole, board)
+            }
+            Game::ShortDeck => hold_em_best(Rules::ShortDeck, hole, board),
+            Game::Plo4 | Game::Plo5 | Game::Plo4HiLo | Game::Plo5HiLo => {
+                omaha_best(hole, board, best_hand_score_indices)
+            }
+            Game::Stud | Game::Stud8 => match hole {
+                [a, b, c, d, e, f, g] => best_hand_score_7_indices(&[*a, *b, *c, *d, *e, *f, *g]),
+                _ => best_hand_score_indices(hole),
+            },
+            Game::Razz => best_low_a5_score_indices(hole),
+        }
+    }
+
+    /// Best 8-or-better low for `hole` on `board` (lower = better), u32::MAX when none
+    /// qualifies or the game has no low.
+    pub fn low_score(&self, hole: &[usize], board: &[usize]) -> u32 {
+        match self {
+            Game::Plo4HiLo | Game::Plo5HiLo => omaha_best(hole, board, best_low8_score_indices),
+            Game::Stud8 => best_low8_score_indices(hole),
+            _ => u32::MAX,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse_cards;
+    use crate::evaluate::HandCategory;
+
+    fn category(game: Game, hole: &str, board: &str) -> HandCategory {
+        let idx = |s: &str| -> Vec<usize> { parse_cards(s).unwrap().iter().map(|c| c.to_idx()).collect() };
+        HandCategory::from_score(game.score(&idx(hole), &idx(board)))
+    }
+
+    #[test]
+    fn test_omaha_uses_exactly_two_hole_cards() {
+        // Four spades on board plus one in hand: a flush in Hold'em, not in Omaha.
+        let board = "2s 7s 9s Js Kd";
+        assert_eq!(category(Game::Holdem, "As 3d", board), HandCategory::Flush);
+        assert_eq!(category(Game::Plo4, "As 3d 4c 8h", board), HandCategory::HighCard);
+        assert_eq!(category(Game::Plo4, "As 3s 4c 8h", board), HandCategory::Flush);
+        // Quads in hand count only as a pair.
+        assert_eq!(category(Game::Plo4, "8c 8d 8h 8s", "2c 5d 9h Js Kd"), HandCategory::OnePair);
+        // Trips on board fill up only with a pocket pair.
+        assert_eq!(category(Game::Plo5, "Ac Ad 3h 4h 6c", "Qs Qh Qd 7c 2d"), HandCategory::FullHouse);
+        assert_eq!(category(Game::Plo4, "3c 7d 6h 8s", "Qs Qh Qd 7c 2d"), HandCategory::ThreeOfAKind);
+        assert_eq!(category(Game::Holdem, "3c 7d", "Qs Qh Qd 7c 2d"), HandCategory::FullHouse);
+    }
+
+    #[test]
+    fn test_omaha_low_uses_two_hole_cards() {
+        let idx = |s: &str| -> Vec<usize> { parse_cards(s).unwrap().iter().map(|c| c.to_idx()).collect() };
+        let board = idx("2c 5d 7h Kd Qs");
+        // A-3 with 2-5-7 makes 7-5-3-2-A
+        let a3 = Game::Plo4HiLo.low_score(&idx("Ac 3d Ks Kh"), &board);
+        assert_eq!(a3, best_low8_score_indices(&idx("Ac 3d 2c 5d 7h")));
+        // only one low card in hand: no low, even with three low cards on board
+        assert_eq!(Game::Plo4HiLo.low_score(&idx("Ac Jd Ks Kh"), &board), u32::MAX);
+        assert_eq!(Game::Plo4.low_score(&idx("Ac 3d Ks Kh"), &board), u32::MAX);
+    }
+
+    #[test]
+    fn test_short_deck_scores_flop_to_river() {
+        let idx = |s: &str| -> Vec<usize> { parse_cards(s).unwrap().iter().map(|c| c.to_idx()).collect() };
+        let flush = Game::ShortDeck.score(&idx("As 9s"), &idx("7s 6s Ks Kh 7d"));
+        let boat = Game::ShortDeck.score(&idx("Kc 7c"), &idx("7s 6s Ks Kh 7d"));
+        assert!(flush < boat);
+        // A-6-7-8-9 is a straight on the flop already
+        assert_eq!(category(Game::ShortDeck, "Ah 6d", "7c 8s 9h"), HandCategory::Straight);
+        assert_eq!(category(Game::Holdem, "Ah 6d", "7c 8s 9h"), HandCategory::HighCard);
+        assert_eq!(
+            Game::ShortDeck.score(&idx("As 9s"), &idx("7s 6s Ks Kh")),
+            best_hand_score_with(Rules::ShortDeck, &idx("As 9s 7s 6s Ks Kh"))
+        );
+    }
+
+    #[test]
+    fn test_parse_game() {
+        assert_eq!(Game::parse("Omaha").unwrap(), Game::Plo4);
+        assert_eq!(Game::parse("plo5").unwrap().hole_cards(), 5);
+        assert_eq!(Game::parse("Razz").unwrap().hole_cards(), 7);
+        assert_eq!(Game::parse("stud8").unwrap().board_cards(), 0);
+        assert!(Game::parse("badugi").is_err());
+    }
+}
@@ -1,5 +1,11 @@
 pub mod cards;
 pub mod evaluate;
+pub mod exact;
+pub mod game;
 pub mod hand_class;
+pub mod outs;
 pub mod preflop_table;
+pub mod range;
+pub mod rng;
 pub mod simulate;
+mod stratify;
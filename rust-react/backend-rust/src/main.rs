@@ -1,31 +1,108 @@
-use texas_equity_api::{cards, hand_class, preflop_table, simulate};
+use texas_equity_api::game::Game;
+use texas_equity_api::simulate::round6;
+use texas_equity_api::{cards, evaluate, exact, hand_class, outs, preflop_table, range, rng, simulate};
 
 use axum::{
     extract::{Query, State},
     http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
     response::IntoResponse,
     routing::{get, post},
     Json, Router,
 };
+use futures_util::Stream;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tower_http::services::ServeDir;
 
+/// Upper bound for `deadlineMs` on adaptive requests.
+const MAX_DEADLINE_MS: u64 = 60_000;
+/// Most scenarios accepted by one /api/equity/batch call.
+const MAX_BATCH_ITEMS: usize = 1_000;
+/// Most Monte Carlo trials (or exact states) one batch may ask for in total.
+const MAX_BATCH_TRIALS: u64 = 20_000_000;
+/// Most `deadlineMs` one batch may ask for in total.
+const MAX_BATCH_DEADLINE_MS: u64 = MAX_DEADLINE_MS;
+
 #[derive(Clone)]
 struct AppState {
     static_dir: Option<PathBuf>,
     preflop_table: Option<Arc<preflop_table::PreflopTable>>,
 }
 
+/// Cards of one request field, each entry holding one or more cards (["As", "Kd"],
+/// ["AsKd"], ["A♠ K♦"]), read into a `Hand` while the body is deserialized. A bad entry
+/// is kept as its parse error rather than rejecting the body, so the 400 response can
+/// still name the card and the character position.
+struct HandField(Result<cards::Hand, cards::ValidationError>);
+
+impl HandField {
+    fn cards(&self) -> Result<Vec<cards::Card>, cards::ValidationError> {
+        self.0.as_ref().map(|hand| hand.0.clone()).map_err(Clone::clone)
+    }
+}
+
+impl Default for HandField {
+    fn default() -> Self {
+        HandField(Ok(cards::Hand::default()))
+    }
+}
+
+impl<'de> Deserialize<'de> for HandField {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries = Vec::<String>::deserialize(deserializer)?;
+        let hand = entries
+            .iter()
+            .map(|entry| entry.parse::<cards::Hand>())
+            .collect::<Result<Vec<_>, _>>()
+            .map(|hands| cards::Hand(hands.into_iter().flat_map(|hand| hand.0).collect()));
+        Ok(HandField(hand))
+    }
+}
+
 #[derive(Deserialize)]
 struct EquityRequest {
     players: u32,
-    hero: Vec<String>,
-    board: Vec<String>,
+    hero: HandField,
+    board: HandField,
     preset: String,
     seed: Option<i64>,
+    /// Fully known opponent hands (e.g. [["Qh","Qc"],["7s","7c"]]), seated first. In stud
+    /// games, each opponent's visible cards; their other cards are dealt at random.
+    #[serde(default)]
+    opponents: Option<Vec<HandField>>,
+    /// Ranges for the remaining opponents ("QQ+, AKs"); missing or empty entries are random hands.
+    #[serde(default)]
+    ranges: Option<Vec<String>>,
+    /// Enumerate exactly when the state count is at most this (default `exact::DEFAULT_EXACT_BUDGET`, 0 disables).
+    #[serde(default, rename = "exactBudget")]
+    exact_budget: Option<u64>,
+    /// Exposed, burned or mucked cards that cannot be dealt to anyone.
+    #[serde(default)]
+    dead: HandField,
+    /// "holdem" (default), "plo4", "plo5", "short_deck", "plo4_hilo", "plo5_hilo", "stud",
+    /// "stud8" or "razz"; sets the deck, hand size and ranking.
+    #[serde(default)]
+    game: Option<String>,
+    /// Adaptive Monte Carlo: stop once the equity standard error is at most this.
+    #[serde(default)]
+    precision: Option<f64>,
+    /// Adaptive Monte Carlo: stop after this many milliseconds.
+    #[serde(default, rename = "deadlineMs")]
+    deadline_ms: Option<u64>,
+    /// Report `seats` even when no opponent hand is known.
+    #[serde(default, rename = "perSeat")]
+    per_seat: bool,
+    /// Monte Carlo generator: "chacha8" (default), "xoshiro256pp" or "pcg32".
+    #[serde(default)]
+    rng: Option<String>,
+    /// Monte Carlo: stratify trials over the next board cards (variance reduction).
+    #[serde(default)]
+    stratify: bool,
 }
 
 #[derive(Serialize)]
@@ -33,6 +110,14 @@ struct EquityResponse {
     win: f64,
     tie: f64,
     lose: f64,
+    /// Hero's pot share: a k-way chop counts 1/k.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    equity: Option<f64>,
+    /// Standard errors (0 for exact results); absent for preflop tables generated without them.
+    #[serde(rename = "stdErr", skip_serializing_if = "Option::is_none")]
+    std_err: Option<simulate::StdErrors>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ci95: Option<simulate::Ci95>,
     trials: u64,
     #[serde(rename = "elapsedMs")]
     elapsed_ms: u64,
@@ -40,6 +125,44 @@ struct EquityResponse {
     method: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     note: Option<String>,
+    /// Why an adaptive run (precision / deadlineMs) stopped.
+    #[serde(rename = "stopReason", skip_serializing_if = "Option::is_none")]
+    stop_reason: Option<simulate::StopReason>,
+    /// Per-seat results (hero first), reported when opponent hands are given or `perSeat` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seats: Option<Vec<SeatResponse>>,
+    /// Final hand category frequencies, strongest category first.
+    #[serde(rename = "handCategories", skip_serializing_if = "Option::is_none")]
+    hand_categories: Option<Vec<CategoryResponse>>,
+    /// Hi-lo games: how often hero scoops, wins only one half, or is quartered.
+    #[serde(rename = "hiLo", skip_serializing_if = "Option::is_none")]
+    hi_lo: Option<simulate::HiLoStats>,
+    /// Generator behind a Monte Carlo result; with the same seed it reproduces the run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rng: Option<&'static str>,
+    /// True when `stratify` was requested and applied.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stratified: bool,
+}
+
+#[derive(Serialize)]
+struct CategoryResponse {
+    category: evaluate::HandCategory,
+    /// Share of trials hero ends with this category.
+    hero: f64,
+    /// Share of trials the best opponent ends with this category.
+    opponent: f64,
+}
+
+#[derive(Serialize)]
+struct SeatResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hand: Option<Vec<cards::Card>>,
+    win: f64,
+    tie: f64,
+    lose: f64,
+    /// Expected pot share of this seat.
+    equity: f64,
 }
 
 #[derive(Serialize)]
@@ -47,12 +170,172 @@ struct ErrorResponse {
     error: String,
     details: Option<String>,
     card: Option<String>,
+    /// Character offset of a card parse error within `card`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    position: Option<usize>,
 }
 
-async fn equity_handler(
-    State(state): State<AppState>,
-    Json(body): Json<EquityRequest>,
-) -> Result<Json<EquityResponse>, (StatusCode, Json<ErrorResponse>)> {
+fn bad_request(e: cards::ValidationError) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            error: e.error,
+            details: e.details,
+            card: e.card,
+            position: e.position,
+        }),
+    )
+}
+
+/// Parse known opponent hands (exactly the game's number of hole cards each).
+/// Each entry may list its cards separately or together ("AsKd").
+fn parse_known_hands(game: Game, hands: &[HandField]) -> Result<Vec<Vec<cards::Card>>, cards::ValidationError> {
+    let mut out = Vec::with_capacity(hands.len());
+    for (i, hand) in hands.iter().enumerate() {
+        let hand = hand.cards()?;
+        if game.is_stud() {
+            // Stud: the cards seen so far (usually the up cards); the rest are dealt at random.
+            if hand.is_empty() || hand.len() > game.hole_cards() {
+                return Err(cards::ValidationError {
+                    error: "invalid_opponent_hand".into(),
+                    details: Some(format!("opponents[{}] must be 1 to {} cards", i, game.hole_cards())),
+                    card: None,
+                    position: None,
+                });
+            }
+        } else if hand.len() != game.hole_cards() {
+            return Err(cards::ValidationError {
+                error: "invalid_opponent_hand".into(),
+                details: Some(format!("opponents[{}] must be exactly {} cards", i, game.hole_cards())),
+                card: None,
+                position: None,
+            });
+        }
+        out.push(hand);
+    }
+    Ok(out)
+}
+
+/// Build the opponent list: known hands take the first seats, then `ranges`
+/// describe the remaining seats in order (missing entries are random hands).
+fn parse_opponents(
+    game: Game,
+    players: u32,
+    known: Vec<Vec<cards::Card>>,
+    ranges: Option<&[String]>,
+) -> Result<Vec<simulate::Opponent>, cards::ValidationError> {
+    let num_opponents = players as usize - 1;
+    if known.len() > num_opponents {
+        return Err(cards::ValidationError {
+            error: "invalid_opponents".into(),
+            details: Some(format!(
+                "{} opponent hands given but there are only {} opponents",
+                known.len(),
+                num_opponents
+            )),
+            card: None,
+            position: None,
+        });
+    }
+    let num_open = num_opponents - known.len();
+    let ranges = ranges.unwrap_or(&[]);
+    if ranges.len() > num_open {
+        return Err(cards::ValidationError {
+            error: "invalid_ranges".into(),
+            details: Some(format!(
+                "ranges has {} entries but only {} opponents are not known",
+                ranges.len(),
+                num_open
+            )),
+            card: None,
+            position: None,
+        });
+    }
+    let mut out: Vec<simulate::Opponent> = known.into_iter().map(simulate::Opponent::Known).collect();
+    for seat in 0..num_open {
+        let spec = ranges.get(seat).map(|s| s.trim()).unwrap_or("");
+        if spec.is_empty() || spec.eq_ignore_ascii_case("random") {
+            out.push(simulate::Opponent::Random);
+        } else if game.hole_cards() != 2 {
+            return Err(cards::ValidationError {
+                error: "invalid_ranges".into(),
+                details: Some(format!("ranges need two-card hands, not {}", game.name())),
+                card: None,
+                position: None,
+            });
+        } else {
+            out.push(simulate::Opponent::Range(range::HandRange::parse(spec)?));
+        }
+    }
+    Ok(out)
+}
+
+/// `precision` / `deadlineMs` turn the Monte Carlo run adaptive; the preset's trial
+/// count is then ignored and the run is capped at `simulate::ADAPTIVE_MAX_TRIALS`.
+fn parse_stop_rule(
+    body: &EquityRequest,
+) -> Result<Option<simulate::StopRule>, (StatusCode, Json<ErrorResponse>)> {
+    if body.precision.is_none() && body.deadline_ms.is_none() {
+        return Ok(None);
+    }
+    if let Some(p) = body.precision {
+        if !(p > 0.0 && p <= 0.5) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "invalid_precision".into(),
+                    details: Some("precision must be in (0, 0.5]".into()),
+                    card: None,
+                    position: None,
+                }),
+            ));
+        }
+    }
+    if let Some(ms) = body.deadline_ms {
+        if !(1..=MAX_DEADLINE_MS).contains(&ms) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "invalid_deadline".into(),
+                    details: Some(format!("deadlineMs must be between 1 and {}", MAX_DEADLINE_MS)),
+                    card: None,
+                    position: None,
+                }),
+            ));
+        }
+    }
+    Ok(Some(simulate::StopRule {
+        target_std_err: body.precision,
+        deadline: body.deadline_ms.map(std::time::Duration::from_millis),
+        max_trials: simulate::ADAPTIVE_MAX_TRIALS,
+    }))
+}
+
+/// Generator, seed and variance reduction for Monte Carlo runs.
+fn parse_sim_options(body: &EquityRequest) -> Result<simulate::SimOptions, (StatusCode, Json<ErrorResponse>)> {
+    let algorithm = match body.rng.as_deref() {
+        None => rng::RngAlgorithm::default(),
+        Some(name) => rng::RngAlgorithm::parse(name).ok_or_else(|| {
+            let names: Vec<&str> = rng::RngAlgorithm::ALL.iter().map(|a| a.name()).collect();
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "invalid_rng".into(),
+                    details: Some(format!("rng must be one of {}, got: {}", names.join(", "), name)),
+                    card: None,
+                    position: None,
+                }),
+            )
+        })?,
+    };
+    Ok(simulate::SimOptions {
+        rng: rng::RngConfig::new(algorithm, body.seed.map(|s| s as u64)),
+        stratify: body.stratify,
+    })
+}
+
+/// Game, cards and opponents parsed and validated from an equity-style request.
+fn parse_deal(body: &EquityRequest) -> Result<simulate::Deal, (StatusCode, Json<ErrorResponse>)> {
     if body.players < 2 || body.players > 10 {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -60,105 +343,588 @@ async fn equity_handler(
                 error: "invalid_players".into(),
                 details: Some("players must be between 2 and 10".into()),
                 card: None,
+                position: None,
             }),
         ));
     }
-    if body.hero.len() != 2 {
+    let game = Game::parse(body.game.as_deref().unwrap_or("holdem")).map_err(bad_request)?;
+    let hero = body.hero.cards().map_err(bad_request)?;
+    let board = body.board.cards().map_err(bad_request)?;
+    let dead = body.dead.cards().map_err(bad_request)?;
+    cards::validate_input(game, &hero, &board, &dead).map_err(bad_request)?;
+    let known = parse_known_hands(game, body.opponents.as_deref().unwrap_or(&[])).map_err(bad_request)?;
+    cards::validate_in_deck(game, known.iter().flatten()).map_err(bad_request)?;
+    cards::validate_unique(
+        hero.iter()
+            .chain(board.iter())
+            .chain(dead.iter())
+            .chain(known.iter().flatten()),
+    )
+    .map_err(bad_request)?;
+    let needed = game.hole_cards() * body.players as usize + game.board_cards() + dead.len();
+    if needed > game.deck_size() {
+        let mut details = format!(
+            "{} players of {} with {} dead cards need {} cards, deck has {}",
+            body.players,
+            game.name(),
+            dead.len(),
+            needed,
+            game.deck_size()
+        );
+        // Real stud deals one shared river card when the deck runs short; that is not modelled.
+        if game.is_stud() {
+            details += &format!(
+                "; stud supports at most {} players, fewer with dead cards (no shared community river card)",
+                game.deck_size() / game.hole_cards()
+            );
+        }
+        return Err(bad_request(cards::ValidationError {
+            error: "not_enough_cards".into(),
+            details: Some(details),
+            card: None,
+            position: None,
+        }));
+    }
+    let opponents = parse_opponents(game, body.players, known, body.ranges.as_deref()).map_err(bad_request)?;
+    Ok(simulate::Deal {
+        game,
+        hero,
+        board,
+        opponents,
+        dead,
+    })
+}
+
+fn incompatible_ranges() -> (StatusCode, Json<ErrorResponse>) {
+    bad_request(cards::ValidationError {
+        error: "incompatible_ranges".into(),
+        details: Some("opponent ranges cannot be dealt together with hero and board".into()),
+        card: None,
+        position: None,
+    })
+}
+
+async fn equity_handler(
+    State(state): State<AppState>,
+    Json(body): Json<EquityRequest>,
+) -> Result<Json<EquityResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // adaptive runs may take up to MAX_DEADLINE_MS; keep them off the async workers
+    tokio::task::spawn_blocking(move || equity(&state, &body))
+        .await
+        .expect("equity worker panicked")
+        .map(Json)
+}
+
+/// One /api/equity request, shared by the single and batch endpoints.
+fn equity(state: &AppState, body: &EquityRequest) -> Result<EquityResponse, (StatusCode, Json<ErrorResponse>)> {
+    equity_with_progress(state, body, None)
+}
+
+/// The preflop table row answering `body`, if there is one. The table is Hold'em only,
+/// assumes a full deck and has no per-seat breakdown; requests asking for a particular
+/// Monte Carlo run (precision, deadlineMs, rng or stratify) are simulated instead.
+fn preflop_table_row<'a>(
+    state: &'a AppState,
+    body: &EquityRequest,
+    deal: &simulate::Deal,
+) -> Result<Option<&'a preflop_table::PreflopRow>, (StatusCode, Json<ErrorResponse>)> {
+    let all_random = deal
+        .opponents
+        .iter()
+        .all(|o| matches!(o, simulate::Opponent::Random));
+    let monte_carlo_options =
+        body.precision.is_some() || body.deadline_ms.is_some() || body.rng.is_some() || body.stratify;
+    if deal.game != Game::Holdem
+        || !deal.board.is_empty()
+        || !all_random
+        || !deal.dead.is_empty()
+        || body.per_seat
+        || monte_carlo_options
+    {
+        return Ok(None);
+    }
+    let Some(hand_class_str) = hand_class::to_hand_class(&deal.hero) else {
         return Err((
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 error: "invalid_hero".into(),
-                details: Some("hero must be exactly 2 cards".into()),
+                details: Some("could not derive hand class from hero cards".into()),
                 card: None,
+                position: None,
             }),
         ));
-    }
-    let hero: Vec<cards::Card> = body
-        .hero
-        .iter()
-        .map(|s| cards::parse_card(s))
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: e.error,
-                    details: e.details,
-                    card: e.card,
-                }),
-            )
-        })?;
-    let board: Vec<cards::Card> = body
-        .board
+    };
+    Ok(state
+        .preflop_table
+        .as_deref()
+        .and_then(|table| table.get(body.players, &hand_class_str)))
+}
+
+/// Interim callback of a streamed request: gets the running Monte Carlo result after every
+/// round and returns false to cancel.
+type Progress<'a> = &'a mut dyn FnMut(&simulate::SimResult) -> bool;
+
+/// `equity`, but with `progress` set a Monte Carlo run goes in rounds (as an adaptive run
+/// capped at the preset's trials) and reports each one. Table lookups and exact
+/// enumeration return at once without progress.
+fn equity_with_progress(
+    state: &AppState,
+    body: &EquityRequest,
+    progress: Option<Progress>,
+) -> Result<EquityResponse, (StatusCode, Json<ErrorResponse>)> {
+    let deal = parse_deal(body)?;
+    let options = parse_sim_options(body)?;
+    let stop_rule = parse_stop_rule(body)?;
+    let any_known = deal
+        .opponents
         .iter()
-        .map(|s| cards::parse_card(s))
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: e.error,
-                    details: e.details,
-                    card: e.card,
-                }),
-            )
-        })?;
-    cards::validate_input(&hero, &board).map_err(|e| {
+        .any(|o| matches!(o, simulate::Opponent::Known(_)));
+
+    if let Some(row) = preflop_table_row(state, body, &deal)? {
+        let note = if body.preset != "standard" || body.seed.is_some() {
+            Some("preset and seed are ignored when using preflop table".into())
+        } else {
+            None
+        };
+        return Ok(EquityResponse {
+            win: row.win,
+            tie: row.tie,
+            lose: row.lose,
+            equity: row.equity,
+            std_err: row.std_err,
+            ci95: row.ci95,
+            trials: state.preflop_table.as_ref().map_or(0, |t| t.trials_per_hand()),
+            elapsed_ms: 0,
+            method: Some("preflop_table".into()),
+            note,
+            stop_reason: None,
+            seats: None,
+            hand_categories: None,
+            hi_lo: None,
+            rng: None,
+            stratified: false,
+        });
+    }
+
+    let budget = body.exact_budget.unwrap_or(exact::DEFAULT_EXACT_BUDGET);
+    let trials = simulate::trials_for_preset(&body.preset);
+    let monte_carlo = budget == 0 || exact::state_count(&deal) > budget;
+    let (result, method, stop_reason) = match (stop_rule, progress) {
+        (rule, Some(progress)) if monte_carlo => {
+            let rule = rule.unwrap_or(simulate::StopRule {
+                target_std_err: None,
+                deadline: None,
+                max_trials: trials,
+            });
+            let (result, reason) = simulate::simulate_with_progress(&deal, rule, options, progress);
+            (result, "monte_carlo", Some(reason))
+        }
+        (Some(rule), None) if monte_carlo => {
+            let (result, reason) = simulate::simulate_adaptive(&deal, rule, options);
+            (result, "monte_carlo", Some(reason))
+        }
+        _ => {
+            let (result, method) = exact::equity_auto(&deal, budget, trials, options);
+            (result, method, None)
+        }
+    };
+    let note = if method == "exact" && (body.preset != "standard" || body.seed.is_some()) {
+        Some("preset and seed are ignored when enumerating exactly".into())
+    } else {
+        None
+    };
+    if result.trials == 0 {
+        return Err(incompatible_ranges());
+    }
+    let seats = if !any_known && !body.per_seat {
+        None
+    } else {
+        let hands = std::iter::once(Some(&deal.hero[..]))
+            .chain(deal.opponents.iter().map(|o| match o {
+                simulate::Opponent::Known(h) => Some(&h[..]),
+                _ => None,
+            }));
+        Some(
+            result
+                .seats
+                .iter()
+                .zip(hands)
+                .map(|(s, hand)| SeatResponse {
+                    hand: hand.map(|h| h.to_vec()),
+                    win: round6(s.win),
+                    tie: round6(s.tie),
+                    lose: round6(s.lose),
+                    equity: round6(s.equity),
+                })
+                .collect(),
+        )
+    };
+    Ok(EquityResponse {
+        win: round6(result.win),
+        tie: round6(result.tie),
+        lose: round6(result.lose),
+        equity: Some(round6(result.equity)),
+        std_err: Some(result.std_err.map(round6)),
+        ci95: Some(result.ci95().map(round6)),
+        trials: result.trials,
+        elapsed_ms: result.elapsed_ms,
+        method: Some(method.into()),
+        note,
+        stop_reason,
+        seats,
+        hand_categories: Some(
+            evaluate::HandCategory::ALL
+                .iter()
+                .enumerate()
+                .map(|(i, &category)| CategoryResponse {
+                    category,
+                    hero: round6(result.hero_categories[i]),
+                    opponent: round6(result.opponent_categories[i]),
+                })
+                .collect(),
+        ),
+        hi_lo: result.hi_lo.map(|h| simulate::HiLoStats {
+            scoop: round6(h.scoop),
+            high_only: round6(h.high_only),
+            low_only: round6(h.low_only),
+            quartered: round6(h.quartered),
+            no_low: round6(h.no_low),
+        }),
+        rng: (method != "exact").then(|| options.rng.algorithm.name()),
+        stratified: result.stratified,
+    })
+}
+
+/// One entry of a batch response: the /api/equity response, or that item's error with
+/// the status code it would have had on its own.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum BatchItemResponse {
+    Ok(Box<EquityResponse>),
+    Err {
+        status: u16,
+        #[serde(flatten)]
+        error: ErrorResponse,
+    },
+}
+
+/// Upper bound on the work of one request, as (trials, deadlineMs): table lookups are
+/// free, exact enumeration costs its states, Monte Carlo its preset's trials and adaptive
+/// runs their deadline, or the adaptive trial cap without one. Invalid requests cost
+/// nothing since they fail before any work.
+fn request_cost(state: &AppState, body: &EquityRequest) -> (u64, u64) {
+    let Ok(deal) = parse_deal(body) else {
+        return (0, 0);
+    };
+    if matches!(preflop_table_row(state, body, &deal), Ok(Some(_))) {
+        return (0, 0);
+    }
+    let budget = body.exact_budget.unwrap_or(exact::DEFAULT_EXACT_BUDGET);
+    let states = exact::state_count(&deal);
+    if budget > 0 && states <= budget {
+        return (states, 0);
+    }
+    match (body.precision, body.deadline_ms) {
+        (_, Some(ms)) => (0, ms),
+        (Some(_), None) => (simulate::ADAPTIVE_MAX_TRIALS, 0),
+        (None, None) => (simulate::trials_for_preset(&body.preset), 0),
+    }
+}
+
+/// Body: a JSON array of /api/equity requests. Each item is parsed and validated on its
+/// own, so one bad item only fails that entry. Items run in parallel on the rayon pool,
+/// each spreading its trials over idle workers, and results come back in request order.
+/// Batches whose items together ask for more than `MAX_BATCH_TRIALS` trials or
+/// `MAX_BATCH_DEADLINE_MS` of deadlines are rejected up front.
+async fn equity_batch_handler(
+    State(state): State<AppState>,
+    Json(items): Json<Vec<serde_json::Value>>,
+) -> Result<Json<Vec<BatchItemResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let too_costly = |details: String| {
         (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
-                error: e.error,
-                details: e.details,
-                card: e.card,
+                error: "batch_too_large".into(),
+                details: Some(details),
+                card: None,
+                position: None,
             }),
         )
-    })?;
-
-    if board.is_empty() {
-        let hand_class_str = match hand_class::to_hand_class(&hero) {
-            Some(h) => h,
-            None => {
-                return Err((
+    };
+    if items.len() > MAX_BATCH_ITEMS {
+        return Err(too_costly(format!(
+            "at most {} requests per batch, got {}",
+            MAX_BATCH_ITEMS,
+            items.len()
+        )));
+    }
+    let requests: Vec<Result<EquityRequest, _>> = items
+        .into_iter()
+        .map(|item| {
+            serde_json::from_value::<EquityRequest>(item).map_err(|e| {
+                (
                     StatusCode::BAD_REQUEST,
                     Json(ErrorResponse {
-                        error: "invalid_hero".into(),
-                        details: Some("could not derive hand class from hero cards".into()),
+                        error: "invalid_request".into(),
+                        details: Some(e.to_string()),
                         card: None,
+                        position: None,
                     }),
-                ));
-            }
+                )
+            })
+        })
+        .collect();
+    let (trials, deadline_ms) = requests
+        .iter()
+        .flatten()
+        .map(|body| request_cost(&state, body))
+        .fold((0u64, 0u64), |(t, d), (bt, bd)| (t.saturating_add(bt), d.saturating_add(bd)));
+    if trials > MAX_BATCH_TRIALS {
+        return Err(too_costly(format!(
+            "batch asks for {} trials in total, at most {} (requests with precision but no deadlineMs count as {})",
+            trials,
+            MAX_BATCH_TRIALS,
+            simulate::ADAPTIVE_MAX_TRIALS
+        )));
+    }
+    if deadline_ms > MAX_BATCH_DEADLINE_MS {
+        return Err(too_costly(format!(
+            "batch deadlines add up to {} ms, at most {}",
+            deadline_ms, MAX_BATCH_DEADLINE_MS
+        )));
+    }
+    let results = tokio::task::spawn_blocking(move || {
+        requests
+            .into_par_iter()
+            .map(|request| match request.and_then(|body| equity(&state, &body)) {
+                Ok(response) => BatchItemResponse::Ok(Box::new(response)),
+                Err((status, Json(error))) => BatchItemResponse::Err {
+                    status: status.as_u16(),
+                    error,
+                },
+            })
+            .collect()
+    })
+    .await
+    .expect("batch worker panicked");
+    Ok(Json(results))
+}
+
+/// Interim estimate pushed as a `progress` event by /api/equity/stream.
+#[derive(Serialize)]
+struct ProgressResponse {
+    trials: u64,
+    win: f64,
+    tie: f64,
+    lose: f64,
+    equity: f64,
+    #[serde(rename = "stdErr")]
+    std_err: simulate::StdErrors,
+    #[serde(rename = "elapsedMs")]
+    elapsed_ms: u64,
+}
+
+impl ProgressResponse {
+    fn new(r: &simulate::SimResult) -> Self {
+        ProgressResponse {
+            trials: r.trials,
+            win: round6(r.win),
+            tie: round6(r.tie),
+            lose: round6(r.lose),
+            equity: round6(r.equity),
+            std_err: r.std_err.map(round6),
+            elapsed_ms: r.elapsed_ms,
+        }
+    }
+}
+
+/// Same body as /api/equity, answered as Server-Sent Events: a `progress` event after
+/// every Monte Carlo round, then one `result` event with the /api/equity response, or an
+/// `error` event. Invalid requests fail up front with the usual 400. Closing the
+/// connection cancels the run.
+async fn equity_stream_handler(
+    State(state): State<AppState>,
+    Json(body): Json<EquityRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<ErrorResponse>)> {
+    parse_deal(&body)?;
+    parse_sim_options(&body)?;
+    parse_stop_rule(&body)?;
+    let (tx, rx) = tokio::sync::mpsc::channel::<Event>(16);
+    tokio::task::spawn_blocking(move || {
+        let progress_tx = tx.clone();
+        let mut progress = |r: &simulate::SimResult| {
+            let event = Event::default().event("progress").json_data(ProgressResponse::new(r));
+            // fails once the client is gone, which cancels the run
+            progress_tx.blocking_send(event.expect("serializable progress")).is_ok()
         };
-        if let Some(ref table) = state.preflop_table {
-            if let Some(row) = table.get(body.players, &hand_class_str) {
-                let note = if body.preset != "standard" || body.seed.is_some() {
-                    Some("preset and seed are ignored when using preflop table".into())
-                } else {
-                    None
-                };
-                return Ok(Json(EquityResponse {
-                    win: row.win,
-                    tie: row.tie,
-                    lose: row.lose,
-                    trials: table.trials_per_hand(),
-                    elapsed_ms: 0,
-                    method: Some("preflop_table".into()),
-                    note,
-                }));
-            }
+        let event = match equity_with_progress(&state, &body, Some(&mut progress)) {
+            Ok(response) => Event::default().event("result").json_data(response),
+            Err((_, Json(error))) => Event::default().event("error").json_data(error),
+        };
+        let _ = tx.blocking_send(event.expect("serializable result"));
+    });
+    let events = futures_util::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|event| (Ok(event), rx))
+    });
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+#[derive(Serialize)]
+struct OutcomeResponse {
+    win: f64,
+    tie: f64,
+    lose: f64,
+    equity: f64,
+}
+
+impl OutcomeResponse {
+    fn new(r: &simulate::SimResult, equity: f64) -> Self {
+        OutcomeResponse {
+            win: round6(r.win),
+            tie: round6(r.tie),
+            lose: round6(r.lose),
+            equity: round6(equity),
         }
     }
+}
 
-    let trials = simulate::trials_for_preset(&body.preset);
-    let seed = body.seed.map(|s| s as u64);
-    let result = simulate::simulate(body.players, &hero, &board, trials, seed);
-    Ok(Json(EquityResponse {
-        win: (result.win * 1e6).round() / 1e6,
-        tie: (result.tie * 1e6).round() / 1e6,
-        lose: (result.lose * 1e6).round() / 1e6,
-        trials: result.trials,
-        elapsed_ms: result.elapsed_ms,
-        method: Some("monte_carlo".into()),
-        note: None,
+#[derive(Serialize)]
+struct NextCardResponse {
+    card: cards::Card,
+    #[serde(flatten)]
+    outcome: OutcomeResponse,
+    category: evaluate::HandCategory,
+    improves: bool,
+    out: bool,
+    clean: bool,
+}
+
+#[derive(Serialize)]
+struct OutsResponse {
+    current: OutcomeResponse,
+    cards: Vec<NextCardResponse>,
+    outs: Vec<cards::Card>,
+    #[serde(rename = "cleanOuts")]
+    clean_outs: Vec<cards::Card>,
+    #[serde(rename = "taintedOuts")]
+    tainted_outs: Vec<cards::Card>,
+    method: String,
+    #[serde(rename = "elapsedMs")]
+    elapsed_ms: u64,
+    /// Generator used when any spot was simulated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rng: Option<&'static str>,
+}
+
+/// Same body as /api/equity; board must be a flop or turn. Monte Carlo spots use a
+/// tenth of the preset's trials per next card. Hi-lo games are rejected: outs there
+/// would have to be judged half by half.
+async fn outs_handler(
+    Json(body): Json<EquityRequest>,
+) -> Result<Json<OutsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    tokio::task::spawn_blocking(move || outs_analysis(&body))
+        .await
+        .expect("outs worker panicked")
+        .map(Json)
+}
+
+fn outs_analysis(body: &EquityRequest) -> Result<OutsResponse, (StatusCode, Json<ErrorResponse>)> {
+    let deal = parse_deal(body)?;
+    if deal.game.is_hi_lo() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "unsupported_game".into(),
+                details: Some(format!("outs are not available for hi-lo games, got {}", deal.game.name())),
+                card: None,
+                position: None,
+            }),
+        ));
+    }
+    if deal.board.len() != 3 && deal.board.len() != 4 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "invalid_board_length".into(),
+                details: Some(format!("outs need a flop or turn board (3 or 4 cards), got {}", deal.board.len())),
+                card: None,
+                position: None,
+            }),
+        ));
+    }
+    let budget = body.exact_budget.unwrap_or(exact::DEFAULT_EXACT_BUDGET);
+    let trials = (simulate::trials_for_preset(&body.preset) / 10).max(5_000);
+    let options = parse_sim_options(body)?;
+    let r = outs::analyze_outs(&deal, budget, trials, options);
+    if r.current.trials == 0 {
+        return Err(incompatible_ranges());
+    }
+    let names = |pred: fn(&outs::NextCard) -> bool| -> Vec<cards::Card> {
+        r.cards.iter().filter(|c| pred(c)).map(|c| c.card).collect()
+    };
+    Ok(OutsResponse {
+        current: OutcomeResponse::new(&r.current, r.current_equity),
+        cards: r
+            .cards
+            .iter()
+            .map(|c| NextCardResponse {
+                card: c.card,
+                outcome: OutcomeResponse::new(&c.result, c.equity),
+                category: c.category,
+                improves: c.improves,
+                out: c.is_out,
+                clean: c.clean,
+            })
+            .collect(),
+        outs: names(|c| c.is_out),
+        clean_outs: names(|c| c.clean),
+        tainted_outs: names(|c| c.is_out && !c.clean),
+        method: r.method.into(),
+        elapsed_ms: r.elapsed_ms,
+        rng: (r.method != "exact").then(|| options.rng.algorithm.name()),
+    })
+}
+
+#[derive(Deserialize)]
+struct EvaluateRequest {
+    cards: HandField,
+}
+
+#[derive(Serialize)]
+struct EvaluateResponse {
+    category: evaluate::HandCategory,
+    #[serde(rename = "categoryName")]
+    category_name: String,
+    description: String,
+    /// Best five cards in display order.
+    cards: Vec<cards::Card>,
+    /// Lower = stronger; comparable across hands.
+    score: u32,
+}
+
+async fn evaluate_handler(
+    Json(body): Json<EvaluateRequest>,
+) -> Result<Json<EvaluateResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let hand = body.cards.cards().map_err(bad_request)?;
+    if !(5..=7).contains(&hand.len()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "invalid_cards_length".into(),
+                details: Some(format!("cards must have 5 to 7 cards, got {}", hand.len())),
+                card: None,
+                position: None,
+            }),
+        ));
+    }
+    cards::validate_unique(hand.iter()).map_err(bad_request)?;
+    let rank = evaluate::evaluate_hand(&hand).expect("5 to 7 cards");
+    Ok(Json(EvaluateResponse {
+        category: rank.category(),
+        category_name: rank.category().name().into(),
+        description: rank.to_string(),
+        cards: rank.cards().to_vec(),
+        score: rank.score(),
     }))
 }
 
@@ -177,15 +943,17 @@ async fn preflop_table_handler(
             error: "invalid_players".into(),
             details: Some("players query param required (2-10)".into()),
             card: None,
+            position: None,
         }),
     ))?;
-    if players < 2 || players > 10 {
+    if !(2..=10).contains(&players) {
         return Err((
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 error: "invalid_players".into(),
                 details: Some("players must be between 2 and 10".into()),
                 card: None,
+                position: None,
             }),
         ));
     }
@@ -195,6 +963,7 @@ async fn preflop_table_handler(
             error: "preflop_table_not_generated".into(),
             details: Some("preflop table not generated.".into()),
             card: None,
+            position: None,
         }),
     ))?;
     let data = table.data.get(&players.to_string()).ok_or((
@@ -203,6 +972,7 @@ async fn preflop_table_handler(
             error: "preflop_table_missing_players".into(),
             details: Some(format!("no data for players={}", players)),
             card: None,
+            position: None,
         }),
     ))?;
     let out = serde_json::json!({
@@ -214,7 +984,7 @@ async fn preflop_table_handler(
 }
 
 async fn serve_spa(State(state): State<AppState>) -> impl IntoResponse {
-    let dir = state.static_dir.as_ref().map(|p| p.as_path()).unwrap_or(std::path::Path::new("dist"));
+    let dir = state.static_dir.as_deref().unwrap_or(std::path::Path::new("dist"));
     let index = dir.join("index.html");
     if let Ok(data) = tokio::fs::read(&index).await {
         return (
@@ -247,12 +1017,17 @@ async fn main() {
     let static_dir = std::env::var("STATIC_DIR").ok().map(PathBuf::from);
     let dist = static_dir.clone().unwrap_or_else(|| PathBuf::from("dist"));
     let preflop_table = load_preflop_table();
+    evaluate::init_tables();
     let state = AppState {
         static_dir: Some(dist.clone()),
         preflop_table,
     };
     let app = Router::new()
         .route("/api/equity", post(equity_handler))
+        .route("/api/equity/batch", post(equity_batch_handler))
+        .route("/api/equity/stream", post(equity_stream_handler))
+        .route("/api/evaluate", post(evaluate_handler))
+        .route("/api/outs", post(outs_handler))
         .route("/api/preflop-table", get(preflop_table_handler))
         .nest_service("/assets", ServeDir::new(dist.join("assets")))
         .route("/", get(serve_spa))
@@ -263,3 +1038,120 @@ async fn main() {
         .await
         .unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(json: serde_json::Value) -> EquityRequest {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn state_with_table() -> AppState {
+        let table: preflop_table::PreflopTable = serde_json::from_value(serde_json::json!({
+            "version": "test",
+            "method": "monte_carlo",
+            "trialsPerHand": 1000,
+            "data": {"2": {"AKo": {"win": 0.64, "tie": 0.02, "lose": 0.34}}}
+        }))
+        .unwrap();
+        AppState {
+            static_dir: None,
+            preflop_table: Some(Arc::new(table)),
+        }
+    }
+
+    #[test]
+    fn test_stop_rule_validated_before_preflop_table() {
+        let state = state_with_table();
+        let body = serde_json::json!({"players": 2, "hero": ["As", "Kd"], "board": [], "preset": "standard"});
+        let ok = equity(&state, &request(body.clone())).ok().unwrap();
+        assert_eq!(ok.method.as_deref(), Some("preflop_table"));
+
+        for (key, value, error) in [
+            ("precision", serde_json::json!(-1.0), "invalid_precision"),
+            ("deadlineMs", serde_json::json!(0), "invalid_deadline"),
+        ] {
+            let mut bad = body.clone();
+            bad[key] = value;
+            let (status, Json(e)) = equity(&state, &request(bad)).err().unwrap();
+            assert_eq!((status, e.error.as_str()), (StatusCode::BAD_REQUEST, error));
+        }
+    }
+
+    #[test]
+    fn test_card_fields_parse_into_hands() {
+        let body = request(serde_json::json!({
+            "players": 3, "hero": ["A♠K♦"], "board": ["7h 8h", "2c"], "preset": "fast",
+            "opponents": [["Qs", "Qd"]], "dead": ["3c,3d"]
+        }));
+        let deal = parse_deal(&body).ok().unwrap();
+        assert_eq!(cards::Hand(deal.hero).to_string(), "As Kd");
+        assert_eq!(deal.board.len(), 3);
+        assert_eq!(deal.dead.len(), 2);
+
+        // a bad card still answers with its entry and the character position
+        let body = request(serde_json::json!({"players": 2, "hero": ["As", "KdQx"], "board": [], "preset": "fast"}));
+        let (status, Json(e)) = parse_deal(&body).err().unwrap();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!((e.error.as_str(), e.card.as_deref(), e.position), ("invalid_suit", Some("KdQx"), Some(3)));
+    }
+
+    #[test]
+    fn test_monte_carlo_options_skip_preflop_table() {
+        let state = state_with_table();
+        for (key, value) in [
+            ("precision", serde_json::json!(0.02)),
+            ("deadlineMs", serde_json::json!(50)),
+            ("rng", serde_json::json!("pcg32")),
+            ("stratify", serde_json::json!(true)),
+        ] {
+            let mut body = serde_json::json!({"players": 2, "hero": ["As", "Kd"], "board": [], "preset": "fast"});
+            body[key] = value;
+            let r = equity(&state, &request(body)).ok().unwrap();
+            assert_eq!(r.method.as_deref(), Some("monte_carlo"), "{}", key);
+            assert!(r.note.is_none());
+        }
+    }
+
+    #[test]
+    fn test_batch_request_cost() {
+        let state = state_with_table();
+        let cost = |json: serde_json::Value| request_cost(&state, &request(json));
+        let mut adaptive = serde_json::json!({"players": 6, "hero": ["AsKd"], "board": ["7h8h2c"], "preset": "high"});
+        assert_eq!(cost(adaptive.clone()), (1_000_000, 0));
+        adaptive["precision"] = serde_json::json!(0.01);
+        assert_eq!(cost(adaptive.clone()), (simulate::ADAPTIVE_MAX_TRIALS, 0));
+        adaptive["deadlineMs"] = serde_json::json!(500);
+        assert_eq!(cost(adaptive), (0, 500));
+        // table lookups and invalid requests are free, exact spots cost their states
+        let heads_up = |hero: &str, board: &[&str]| {
+            serde_json::json!({"players": 2, "hero": [hero], "board": board, "preset": "high"})
+        };
+        assert_eq!(cost(heads_up("AsKd", &[])), (0, 0));
+        assert_eq!(cost(heads_up("AxKd", &[])), (0, 0));
+        assert_eq!(cost(heads_up("AsKd", &["7h", "8h", "2c", "3d", "4s"])), (990, 0));
+    }
+
+    #[test]
+    fn test_stud_player_limit() {
+        let body = |players: u32| {
+            request(serde_json::json!({
+                "players": players, "hero": ["AsKdQh"], "board": [], "preset": "fast", "game": "stud"
+            }))
+        };
+        assert!(parse_deal(&body(7)).is_ok());
+        let (status, Json(e)) = parse_deal(&body(8)).err().unwrap();
+        assert_eq!((status, e.error.as_str()), (StatusCode::BAD_REQUEST, "not_enough_cards"));
+        assert!(e.details.unwrap().contains("at most 7 players"));
+    }
+
+    #[test]
+    fn test_outs_reject_hi_lo_games() {
+        let body = request(serde_json::json!({
+            "players": 2, "hero": ["AhKh2c3d"], "board": ["Qh7h4c"], "preset": "fast", "game": "plo4_hilo"
+        }));
+        let (status, Json(e)) = outs_analysis(&body).err().unwrap();
+        assert_eq!((status, e.error.as_str()), (StatusCode::BAD_REQUEST, "unsupported_game"));
+    }
+}
@@ -0,0 +1,160 @@
+// Outs / next-card analysis: hero's equity after each unseen turn or river card.
+//
+// An out is a card that improves hero's hand category and raises hero's equity; on
+// simulated spots the rise must exceed the combined standard error, so sampling noise
+// alone does not make a card an out. It is clean when, after the card, no possible
+// opponent holding (known hand, range combo or any two cards) is ahead of hero's made
+// hand; otherwise it is tainted. Only high hands are scored, so hi-lo games are not
+// supported.
+
+use crate::cards::{any_combination, Card, CardSet};
+use crate::evaluate::HandCategory;
+use crate::exact;
+use crate::game::Game;
+use crate::simulate::{Deal, Opponent, SimOptions, SimResult};
+use std::time::Instant;
+
+/// Result for one possible next card.
+#[derive(Debug, Clone)]
+pub struct NextCard {
+    pub card: Card,
+    pub result: SimResult,
+    /// Hero's pot share after the card.
+    pub equity: f64,
+    /// Hero's hand category on the board including this card.
+    pub category: HandCategory,
+    pub improves: bool,
+    pub is_out: bool,
+    pub clean: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct OutsResult {
+    /// Hero's result on the current board.
+    pub current: SimResult,
+    pub current_equity: f64,
+    pub cards: Vec<NextCard>,
+    /// "exact" if every evaluation was enumerated, else "monte_carlo".
+    pub method: &'static str,
+    pub elapsed_ms: u64,
+}
+
+fn indices(cards: &[Card]) -> Vec<usize> {
+    cards.iter().map(|c| c.to_idx()).collect()
+}
+
+fn hand_score(game: Game, hole: &[Card], board: &[Card]) -> u32 {
+    game.score(&indices(hole), &indices(board))
+}
+
+/// True if some opponent holding beats hero on the deal's board right now.
+fn anyone_ahead(deal: &Deal) -> bool {
+    let game = deal.game;
+    let board = indices(&deal.board);
+    let target = game.score(&indices(&deal.hero), &board);
+    let known = deal.known_cards();
+    let beats = |hole: &[usize]| game.score(hole, &board) < target;
+    deal.opponents_without(&known).iter().any(|o| match o {
+        Opponent::Known(h) => beats(&indices(h)),
+        Opponent::Range(r) => r.combos().iter().any(|c| beats(c)),
+        Opponent::Random => any_combination(&game.deck(&known), game.hole_cards(), CardSet::EMPTY, &mut |h| beats(h)),
+    })
+}
+
+/// Evaluate every unseen next card (not known or dead) for a flop (3) or turn (4) board.
+/// Each spot is enumerated exactly when it fits `budget`, else simulated with `trials`.
+pub fn analyze_outs(deal: &Deal, budget: u64, trials: u64, options: impl Into<SimOptions>) -> OutsResult {
+    let options = options.into();
+    let start = Instant::now();
+    let known = deal.known_cards();
+    let (current, mut method) = exact::equity_auto(deal, budget, trials, options);
+    let current_equity = current.equity;
+    let current_score = hand_score(deal.game, &deal.hero, &deal.board);
+    let mut cards = Vec::new();
+    for idx in deal.game.deck(&known) {
+        let card = Card::from_idx(idx);
+        let next = deal.with_board_card(card);
+        let (result, m) = exact::equity_auto(&next, budget, trials, options);
+        if m != "exact" {
+            method = m;
+        }
+        let equity = result.equity;
+        let score = hand_score(deal.game, &next.hero, &next.board);
+        let category = HandCategory::from_score(score);
+        // by the game's own category order (short deck ranks a flush over a full house)
+        let improves = HandCategory::strength(score) < HandCategory::strength(current_score);
+        // exact results have zero standard error, so there any rise counts
+        let noise = result.std_err.equity.hypot(current.std_err.equity);
+        let is_out = improves && equity - current_equity > noise;
+        let clean = is_out && !anyone_ahead(&next);
+        cards.push(NextCard {
+            card,
+            result,
+            equity,
+            category,
+            improves,
+            is_out,
+            clean,
+        });
+    }
+    OutsResult {
+        current,
+        current_equity,
+        cards,
+        method,
+        elapsed_ms: start.elapsed().as_millis() as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse_cards;
+
+    fn next(r: &OutsResult, card: &str) -> NextCard {
+        r.cards.iter().find(|c| c.card.to_string() == card).unwrap().clone()
+    }
+
+    #[test]
+    fn test_flush_draw_against_set() {
+        let hero = parse_cards("Ah Kh").unwrap();
+        let board = parse_cards("Qh 7h 2c 9d").unwrap();
+        let villain = parse_cards("Qs Qd").unwrap();
+        let r = analyze_outs(&Deal::holdem(&hero, &board, &[Opponent::Known(villain)]), 1_000_000, 0, None);
+        assert_eq!(r.method, "exact");
+        assert_eq!(r.cards.len(), 44);
+        let outs: Vec<String> = r.cards.iter().filter(|c| c.is_out).map(|c| c.card.to_string()).collect();
+        // hearts that pair the board give the set a full house; pairing A/K still loses
+        assert_eq!(outs, ["3h", "4h", "5h", "6h", "8h", "Th", "Jh"]);
+        assert!(r.cards.iter().filter(|c| c.is_out).all(|c| c.clean));
+        assert!(next(&r, "Ac").improves && !next(&r, "Ac").is_out);
+    }
+
+    #[test]
+    fn test_tainted_outs_against_random_hand() {
+        let hero = parse_cards("Ah Kh").unwrap();
+        let board = parse_cards("Qh 7h 2c 9d").unwrap();
+        let r = analyze_outs(&Deal::holdem(&hero, &board, &[Opponent::Random]), 1_000_000, 0, None);
+        let paired = next(&r, "2h");
+        assert!(paired.is_out && !paired.clean);
+        let clean = next(&r, "3h");
+        assert!(clean.is_out && clean.clean);
+        assert_eq!(clean.category, HandCategory::Flush);
+        assert!(clean.equity > r.current_equity);
+    }
+
+    #[test]
+    fn test_simulated_outs_clear_the_noise() {
+        let hero = parse_cards("Ah Kh").unwrap();
+        let board = parse_cards("Qh 7h 2c 9d").unwrap();
+        let deal = Deal::holdem(&hero, &board, &[Opponent::Random]);
+        let r = analyze_outs(&deal, 0, 20_000, Some(3));
+        assert_eq!(r.method, "monte_carlo");
+        for c in &r.cards {
+            let noise = c.result.std_err.equity.hypot(r.current.std_err.equity);
+            assert!(noise > 0.0);
+            assert_eq!(c.is_out, c.improves && c.equity - r.current_equity > noise, "{}", c.card);
+        }
+        assert!(next(&r, "3h").is_out && next(&r, "3h").clean);
+    }
+}
@@ -1,5 +1,6 @@
 // プリフロップ固定表の読み込みと参照。
 
+use crate::simulate::{Ci95, StdErrors};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
@@ -9,6 +10,13 @@ pub struct PreflopRow {
     pub win: f64,
     pub tie: f64,
     pub lose: f64,
+    /// ポット取り分（k 人での引き分けは 1/k）・標準誤差・95%信頼区間。古い表には無い。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equity: Option<f64>,
+    #[serde(default, rename = "stdErr", skip_serializing_if = "Option::is_none")]
+    pub std_err: Option<StdErrors>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ci95: Option<Ci95>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
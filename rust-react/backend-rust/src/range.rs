@@ -0,0 +1,258 @@
+// Opponent hand ranges: "QQ+, AKs, AJo+, 76s" -> weighted list of concrete combos.
+// Syntax per comma-separated token:
+//   "AA", "AKs", "AKo", "AK" (= AKs + AKo)
+//   "QQ+" (QQ..AA), "AJo+" (AJo..AKo), "TT-77", "A5s-A2s"
+//   optional weight suffix "AKs:0.5" (default 1.0)
+
+use crate::cards::{Card, CardSet, Rank, ValidationError};
+use crate::hand_class::hand_class_combos;
+use crate::rng::UniformRng;
+
+/// Maximum whole-deal attempts before a trial is given up as impossible.
+pub const MAX_DEAL_ATTEMPTS: usize = 1000;
+
+#[derive(Debug, Clone)]
+pub struct HandRange {
+    combos: Vec<[usize; 2]>,
+    cumulative: Vec<f64>,
+}
+
+fn range_error(details: String, token: &str) -> ValidationError {
+    ValidationError {
+        error: "invalid_range".into(),
+        details: Some(details),
+        card: Some(token.into()),
+        position: None,
+    }
+}
+
+fn rank_of(ch: char, token: &str) -> Result<Rank, ValidationError> {
+    Rank::from_char(ch).ok_or_else(|| range_error(format!("Rank must be one of AKQJT98765432, got: {}", ch), token))
+}
+
+/// Hand class string for two ranks and an optional suitedness suffix.
+fn class_name(r1: Rank, r2: Rank, suffix: &str) -> String {
+    let (hi, lo) = if r1 >= r2 { (r1, r2) } else { (r2, r1) };
+    if hi == lo {
+        format!("{}{}", hi, lo)
+    } else {
+        format!("{}{}{}", hi, lo, suffix)
+    }
+}
+
+/// Ranks from `a` to `b` inclusive, highest first.
+fn ranks_down(a: Rank, b: Rank) -> impl Iterator<Item = Rank> {
+    Rank::ALL[a.min(b) as usize..=a.max(b) as usize].iter().rev().copied()
+}
+
+/// A single class pattern: (high rank, low rank, suffix) with suffix "", "s" or "o".
+fn parse_class(s: &str, token: &str) -> Result<(Rank, Rank, String), ValidationError> {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() != 2 && chars.len() != 3 {
+        return Err(range_error(
+            format!("Hand class must look like AA, AKs, AKo or AK, got: {}", s),
+            token,
+        ));
+    }
+    let r1 = rank_of(chars[0], token)?;
+    let r2 = rank_of(chars[1], token)?;
+    let (hi, lo) = if r1 >= r2 { (r1, r2) } else { (r2, r1) };
+    let suffix = match chars.get(2).map(|c| c.to_ascii_lowercase()) {
+        None => String::new(),
+        Some('s') if hi != lo => "s".into(),
+        Some('o') if hi != lo => "o".into(),
+        Some(c) => {
+            return Err(range_error(format!("Invalid suffix '{}' in {}", c, s), token));
+        }
+    };
+    Ok((hi, lo, suffix))
+}
+
+fn push_classes(out: &mut Vec<String>, hi: Rank, lo: Rank, suffix: &str) {
+    if hi != lo && suffix.is_empty() {
+        out.push(class_name(hi, lo, "s"));
+        out.push(class_name(hi, lo, "o"));
+    } else {
+        out.push(class_name(hi, lo, suffix));
+    }
+}
+
+/// Expand one token (without weight) into hand class names.
+fn expand_token(body: &str, token: &str) -> Result<Vec<String>, ValidationError> {
+    let mut out = Vec::new();
+    if let Some(base) = body.strip_suffix('+') {
+        let (hi, lo, suffix) = parse_class(base, token)?;
+        if hi == lo {
+            for &r in &Rank::ALL[hi as usize..] {
+                out.push(class_name(r, r, ""));
+            }
+        } else {
+            for &k in &Rank::ALL[lo as usize..hi as usize] {
+                push_classes(&mut out, hi, k, &suffix);
+            }
+        }
+    } else if let Some((a, b)) = body.split_once('-') {
+        let (hi_a, lo_a, suf_a) = parse_class(a, token)?;
+        let (hi_b, lo_b, suf_b) = parse_class(b, token)?;
+        let pairs = hi_a == lo_a && hi_b == lo_b;
+        if pairs {
+            for r in ranks_down(hi_a, hi_b) {
+                out.push(class_name(r, r, ""));
+            }
+        } else if hi_a == hi_b && suf_a == suf_b && hi_a != lo_a && hi_b != lo_b {
+            for k in ranks_down(lo_a, lo_b) {
+                push_classes(&mut out, hi_a, k, &suf_a);
+            }
+        } else {
+            return Err(range_error(
+                format!("Range endpoints must share shape (e.g. TT-77, A5s-A2s), got: {}", body),
+                token,
+            ));
+        }
+    } else {
+        let (hi, lo, suffix) = parse_class(body, token)?;
+        push_classes(&mut out, hi, lo, &suffix);
+    }
+    Ok(out)
+}
+
+impl HandRange {
+    /// Parse a comma-separated range. Later duplicates of the same class overwrite its weight.
+    pub fn parse(s: &str) -> Result<Self, ValidationError> {
+        let mut weights: Vec<(String, f64)> = Vec::new();
+        for raw in s.split(',') {
+            let token = raw.trim();
+            if token.is_empty() {
+                continue;
+            }
+            let (body, weight) = match token.split_once(':') {
+                Some((b, w)) => {
+                    let w: f64 = w.trim().parse().map_err(|_| {
+                        range_error(format!("Weight must be a number, got: {}", w), token)
+                    })?;
+                    if !(0.0..=1.0).contains(&w) {
+                        return Err(range_error(format!("Weight must be in 0..1, got: {}", w), token));
+                    }
+                    (b.trim(), w)
+                }
+                None => (token, 1.0),
+            };
+            let body: String = body.chars().filter(|c| !c.is_whitespace()).collect();
+            for class in expand_token(&body, token)? {
+                match weights.iter_mut().find(|(c, _)| *c == class) {
+                    Some(entry) => entry.1 = weight,
+                    None => weights.push((class, weight)),
+                }
+            }
+        }
+        let mut combos = Vec::new();
+        let mut cumulative = Vec::new();
+        let mut total = 0.0;
+        for (class, weight) in &weights {
+            if *weight <= 0.0 {
+                continue;
+            }
+            for [a, b] in hand_class_combos(class).unwrap_or_default() {
+                total += weight;
+                combos.push([a.to_idx(), b.to_idx()]);
+                cumulative.push(total);
+            }
+        }
+        if combos.is_empty() {
+            return Err(ValidationError {
+                error: "empty_range".into(),
+                details: Some(format!("Range contains no hands: {}", s)),
+                card: None,
+                position: None,
+            });
+        }
+        Ok(HandRange { combos, cumulative })
+    }
+
+    pub fn combos(&self) -> &[[usize; 2]] {
+        &self.combos
+    }
+
+    /// Number of combos (before weighting).
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    /// Combos paired with their weights.
+    pub fn weighted(&self) -> impl Iterator<Item = ([usize; 2], f64)> + '_ {
+        let prev = std::iter::once(0.0).chain(self.cumulative.iter().copied());
+        self.combos
+            .iter()
+            .zip(self.cumulative.iter().zip(prev))
+            .map(|(c, (cum, p))| (*c, cum - p))
+    }
+
+    /// Drop combos that use any of `known` (hero/board). Weights are preserved.
+    pub fn without_cards(&self, known: &[Card]) -> HandRange {
+        let known: CardSet = known.iter().collect();
+        let mut combos = Vec::new();
+        let mut cumulative = Vec::new();
+        let mut prev = 0.0;
+        let mut total = 0.0;
+        for (combo, &cum) in self.combos.iter().zip(&self.cumulative) {
+            let w = cum - prev;
+            prev = cum;
+            if !known.is_disjoint(CardSet::from_indices(combo)) {
+                continue;
+            }
+            total += w;
+            combos.push(*combo);
+            cumulative.push(total);
+        }
+        HandRange { combos, cumulative }
+    }
+
+    /// Draw one combo proportionally to its weight. Card removal is handled by the caller.
+    pub fn sample(&self, rng: &mut impl UniformRng) -> [usize; 2] {
+        let total = *self.cumulative.last().unwrap_or(&0.0);
+        let r = rng.unit_f64() * total;
+        let i = self.cumulative.partition_point(|&c| c <= r);
+        self.combos[i.min(self.combos.len() - 1)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse_card;
+
+    fn combos(s: &str) -> usize {
+        HandRange::parse(s).unwrap().len()
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(combos("AA"), 6);
+        assert_eq!(combos("QQ+"), 18);
+        assert_eq!(combos("AKs, AKo"), 16);
+        assert_eq!(combos("AK"), 16);
+        assert_eq!(combos("AJo+"), 36);
+        assert_eq!(combos("TT-77"), 24);
+        assert_eq!(combos("A5s-A2s"), 16);
+        assert_eq!(combos("QQ+, AKs, AJo+, 76s"), 18 + 4 + 36 + 4);
+        assert!(HandRange::parse("AKx").is_err());
+        assert!(HandRange::parse("AK-QJ").is_err());
+        assert!(HandRange::parse("").is_err());
+    }
+
+    #[test]
+    fn test_without_cards_and_sample() {
+        let r = HandRange::parse("AA").unwrap();
+        let r = r.without_cards(&[parse_card("As").unwrap()]);
+        assert_eq!(r.len(), 3);
+        let mut rng = crate::cards::seeded_rng(7);
+        for _ in 0..100 {
+            let c = r.sample(&mut rng);
+            assert!(r.combos().contains(&c));
+        }
+    }
+}
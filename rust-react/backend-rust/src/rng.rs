@@ -0,0 +1,333 @@
+// Random number generation for simulation: a choice of seedable algorithms with
+// independent per-worker streams, plus unbiased bounded integers and uniform floats.
+// Everything here is implemented or pinned in this crate, so a given (algorithm, seed)
+// draws the same numbers on every machine and across dependency upgrades.
+
+use rand_chacha::rand_core::{impls, Error, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+/// Generator family used by the simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RngAlgorithm {
+    /// ChaCha with 8 rounds (`rand_chacha`); 2^64 streams per seed.
+    #[default]
+    #[serde(rename = "chacha8")]
+    ChaCha8,
+    /// xoshiro256++; streams are seeded from (seed, stream) through SplitMix64.
+    #[serde(rename = "xoshiro256pp")]
+    Xoshiro256PlusPlus,
+    /// PCG-XSH-RR 64/32; the stream selects the LCG increment (2^63 streams per seed).
+    #[serde(rename = "pcg32")]
+    Pcg32,
+}
+
+impl RngAlgorithm {
+    pub const ALL: [RngAlgorithm; 3] = [
+        RngAlgorithm::ChaCha8,
+        RngAlgorithm::Xoshiro256PlusPlus,
+        RngAlgorithm::Pcg32,
+    ];
+
+    /// API name, as accepted by `parse` and reported in responses.
+    pub fn name(self) -> &'static str {
+        match self {
+            RngAlgorithm::ChaCha8 => "chacha8",
+            RngAlgorithm::Xoshiro256PlusPlus => "xoshiro256pp",
+            RngAlgorithm::Pcg32 => "pcg32",
+        }
+    }
+
+    /// Case-insensitive; "xoshiro256++" and "xoshiro" are accepted for xoshiro256pp.
+    pub fn parse(s: &str) -> Option<RngAlgorithm> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "xoshiro256++" | "xoshiro" => Some(RngAlgorithm::Xoshiro256PlusPlus),
+            "pcg" => Some(RngAlgorithm::Pcg32),
+            s => RngAlgorithm::ALL.into_iter().find(|a| a.name() == s),
+        }
+    }
+}
+
+/// Algorithm and optional seed for a run. Without a seed, each batch draws a fresh one
+/// from the OS-seeded thread RNG.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RngConfig {
+    pub algorithm: RngAlgorithm,
+    pub seed: Option<u64>,
+}
+
+impl RngConfig {
+    pub fn new(algorithm: RngAlgorithm, seed: Option<u64>) -> Self {
+        RngConfig { algorithm, seed }
+    }
+
+    /// The seed to use for a batch: the configured one, or a fresh random one.
+    pub fn batch_seed(&self) -> u64 {
+        self.seed.unwrap_or_else(rand::random)
+    }
+}
+
+/// Seed only, default algorithm; lets callers pass `Some(seed)` or `None`.
+impl From<Option<u64>> for RngConfig {
+    fn from(seed: Option<u64>) -> Self {
+        RngConfig { algorithm: RngAlgorithm::default(), seed }
+    }
+}
+
+/// A seeded generator of any supported algorithm. An enum rather than `Box<dyn RngCore>`
+/// so the per-draw dispatch is a predictable match instead of a vtable call. There is
+/// one per worker, so the ChaCha buffer making every variant large does not matter.
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum SimRng {
+    ChaCha8(ChaCha8Rng),
+    Xoshiro256PlusPlus(Xoshiro256PlusPlus),
+    Pcg32(Pcg32),
+}
+
+impl SimRng {
+    /// Generator for `stream` of `seed`. Different streams of one seed are independent; PCG
+    /// only uses the low 63 bits of `stream`.
+    pub fn new(algorithm: RngAlgorithm, seed: u64, stream: u64) -> Self {
+        match algorithm {
+            RngAlgorithm::ChaCha8 => {
+                let mut r = ChaCha8Rng::seed_from_u64(seed);
+                r.set_stream(stream);
+                SimRng::ChaCha8(r)
+            }
+            RngAlgorithm::Xoshiro256PlusPlus => SimRng::Xoshiro256PlusPlus(Xoshiro256PlusPlus::new(seed, stream)),
+            RngAlgorithm::Pcg32 => SimRng::Pcg32(Pcg32::new(seed, stream)),
+        }
+    }
+
+    pub fn algorithm(&self) -> RngAlgorithm {
+        match self {
+            SimRng::ChaCha8(_) => RngAlgorithm::ChaCha8,
+            SimRng::Xoshiro256PlusPlus(_) => RngAlgorithm::Xoshiro256PlusPlus,
+            SimRng::Pcg32(_) => RngAlgorithm::Pcg32,
+        }
+    }
+}
+
+impl RngCore for SimRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            SimRng::ChaCha8(r) => r.next_u32(),
+            SimRng::Xoshiro256PlusPlus(r) => r.next_u32(),
+            SimRng::Pcg32(r) => r.next_u32(),
+        }
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            SimRng::ChaCha8(r) => r.next_u64(),
+            SimRng::Xoshiro256PlusPlus(r) => r.next_u64(),
+            SimRng::Pcg32(r) => r.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Sampling helpers on top of any `RngCore`, with fixed algorithms (rand's own
+/// `gen_range` may change between versions).
+pub trait UniformRng: RngCore {
+    /// Uniform integer in `0..n` without modulo bias (Lemire's multiply-and-reject).
+    /// Panics if `n` is 0 or does not fit in u32.
+    #[inline]
+    fn below(&mut self, n: usize) -> usize {
+        let n = u32::try_from(n).expect("bound fits in u32");
+        assert!(n > 0, "empty range");
+        let mut m = self.next_u32() as u64 * n as u64;
+        if (m as u32) < n {
+            let threshold = n.wrapping_neg() % n;
+            while (m as u32) < threshold {
+                m = self.next_u32() as u64 * n as u64;
+            }
+        }
+        (m >> 32) as usize
+    }
+
+    /// Uniform float in [0, 1) with 53 random bits.
+    #[inline]
+    fn unit_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+}
+
+impl<R: RngCore + ?Sized> UniformRng for R {}
+
+/// SplitMix64 step, used to expand 64-bit seeds into larger states.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// xoshiro256++ (Blackman & Vigna).
+#[derive(Debug, Clone)]
+pub struct Xoshiro256PlusPlus {
+    s: [u64; 4],
+}
+
+impl Xoshiro256PlusPlus {
+    /// State from SplitMix64 run over `seed`, then over `stream` offset by that first
+    /// output. The first two words alone give back (seed, stream), so distinct pairs never
+    /// share a state. Jumping would give provably disjoint streams, but costs O(stream)
+    /// per generator.
+    pub fn new(seed: u64, stream: u64) -> Self {
+        let mut sm = seed;
+        let first = splitmix64(&mut sm);
+        let mut sm = stream.wrapping_add(first);
+        let mut s = [first, 0, 0, 0];
+        for x in &mut s[1..] {
+            *x = splitmix64(&mut sm);
+        }
+        if s == [0; 4] {
+            s[0] = 1;
+        }
+        Xoshiro256PlusPlus { s }
+    }
+
+    pub fn from_state(s: [u64; 4]) -> Self {
+        Xoshiro256PlusPlus { s }
+    }
+}
+
+impl RngCore for Xoshiro256PlusPlus {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let s = &mut self.s;
+        let result = s[0].wrapping_add(s[3]).rotate_left(23).wrapping_add(s[0]);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// PCG-XSH-RR with 64-bit state and 32-bit output (O'Neill's `pcg32`).
+#[derive(Debug, Clone)]
+pub struct Pcg32 {
+    state: u64,
+    inc: u64,
+}
+
+impl Pcg32 {
+    const MULTIPLIER: u64 = 6364136223846793005;
+
+    /// Same seeding as `pcg32_srandom(seed, stream)` in the reference implementation. The
+    /// increment must be odd, so only the low 63 bits of `stream` count: `s` and
+    /// `s | 1 << 63` are the same stream.
+    pub fn new(seed: u64, stream: u64) -> Self {
+        let mut r = Pcg32 { state: 0, inc: (stream << 1) | 1 };
+        r.step();
+        r.state = r.state.wrapping_add(seed);
+        r.step();
+        r
+    }
+
+    #[inline]
+    fn step(&mut self) {
+        self.state = self.state.wrapping_mul(Self::MULTIPLIER).wrapping_add(self.inc);
+    }
+}
+
+impl RngCore for Pcg32 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_u32(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_outputs() {
+        // pcg32_srandom(42, 54), from the PCG reference demo
+        let mut pcg = Pcg32::new(42, 54);
+        let out: Vec<u32> = (0..6).map(|_| pcg.next_u32()).collect();
+        assert_eq!(out, [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e]);
+        // xoshiro256++ from state [1, 2, 3, 4]: rotl(1 + 4, 23) + 1
+        let mut x = Xoshiro256PlusPlus::from_state([1, 2, 3, 4]);
+        assert_eq!(x.next_u64(), (5u64 << 23) + 1);
+    }
+
+    #[test]
+    fn test_streams_and_sampling() {
+        for alg in RngAlgorithm::ALL {
+            assert_eq!(RngAlgorithm::parse(alg.name()), Some(alg));
+            let draw = |seed, stream| {
+                let mut r = SimRng::new(alg, seed, stream);
+                (0..4).map(|_| r.next_u64()).collect::<Vec<_>>()
+            };
+            assert_eq!(draw(1, 0), draw(1, 0));
+            assert_ne!(draw(1, 0), draw(1, 1));
+            assert_ne!(draw(1, 0), draw(2, 0));
+            // neighbouring (seed, stream) pairs all start differently
+            let mut firsts: Vec<u64> = (0..16).flat_map(|seed| (0..16).map(move |s| draw(seed, s)[0])).collect();
+            firsts.sort_unstable();
+            firsts.dedup();
+            assert_eq!(firsts.len(), 256, "{:?}", alg);
+
+            // every value of a small bound shows up about equally often
+            let mut r = SimRng::new(alg, 7, 0);
+            let mut counts = [0u32; 52];
+            for _ in 0..52_000 {
+                counts[r.below(52)] += 1;
+            }
+            assert!(counts.iter().all(|&c| (800..1200).contains(&c)), "{:?} {:?}", alg, counts);
+            assert!((0..1000).all(|_| (0.0..1.0).contains(&r.unit_f64())));
+        }
+        assert_eq!(RngAlgorithm::parse("Xoshiro256++"), Some(RngAlgorithm::Xoshiro256PlusPlus));
+        assert_eq!(RngAlgorithm::parse("mt19937"), None);
+    }
+}
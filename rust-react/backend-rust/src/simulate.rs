@@ -1,36 +1,18 @@
 // Monte Carlo: run trials, return win/tie/lose. Rayon parallel, partial Fisher–Yates.
+// Opponents are dealt uniformly at random, from a weighted hand range, or are fully known.
 
-use crate::cards::{build_deck, draw_indices, Card};
-use crate::evaluate::best_hand_score_7_indices;
-use rand::Rng;
+use crate::cards::{Card, CardSet};
+use crate::evaluate::HandCategory;
+use crate::game::Game;
+use crate::range::{HandRange, MAX_DEAL_ATTEMPTS};
+use crate::rng::{RngConfig, SimRng, UniformRng};
+use crate::stratify::{ClassSums, Strata, Stratum};
 use rayon::prelude::*;
-use std::sync::atomic::{AtomicU64, Ordering};
+use serde::{Deserialize, Serialize};
 use std::time::Instant;
 
-/// RNG per worker: concrete type to avoid Box<dyn FnMut> and vtable cost.
-enum WorkerRng {
-    Seeded(u64),
-    Thread(rand::rngs::ThreadRng),
-}
-
-impl WorkerRng {
-    fn new(seed: Option<u64>, worker_id: usize) -> Self {
-        match seed {
-            Some(s) => WorkerRng::Seeded(s.wrapping_add(worker_id as u64 * 1_000_000_000)),
-            None => WorkerRng::Thread(rand::rngs::ThreadRng::default()),
-        }
-    }
-
-    fn next_f64(&mut self) -> f64 {
-        match self {
-            WorkerRng::Seeded(s) => {
-                *s = s.wrapping_mul(1664525).wrapping_add(1013904223);
-                (*s >> 32) as f64 / (1u64 << 32) as f64
-            }
-            WorkerRng::Thread(r) => r.gen(),
-        }
-    }
-}
+/// Trials per logical block of a batch; each block has its own RNG stream.
+pub const BLOCK_TRIALS: u64 = 1_000;
 
 const PRESET_TRIALS: &[(&str, u64)] = &[
     ("fast", 50_000),
@@ -47,65 +29,735 @@ pub fn trials_for_preset(preset: &str) -> u64 {
         .unwrap_or(200_000)
 }
 
-fn run_one_trial(
-    hero: &[Card],
-    board_len: usize,
-    deck: &mut [usize],
-    num_opponents: usize,
+/// Round to six decimals, the precision of API responses and the preflop table.
+pub fn round6(x: f64) -> f64 {
+    (x * 1e6).round() / 1e6
+}
+
+/// How an opponent's hole cards are dealt.
+#[derive(Debug, Clone)]
+pub enum Opponent {
+    /// Uniformly random hole cards from the remaining deck.
+    Random,
+    /// A weighted two-card combo from a range, respecting card removal (Hold'em only).
+    Range(HandRange),
+    /// Known hole cards, removed from the deck. In stud games these may be only some of
+    /// the seven (e.g. the up cards); the rest are dealt at random.
+    Known(Vec<Card>),
+}
+
+impl Opponent {
+    /// How many of this seat's `hole` cards are dealt from the deck at random.
+    pub fn unknown_cards(&self, hole: usize) -> usize {
+        match self {
+            Opponent::Random => hole,
+            Opponent::Range(_) => 0,
+            Opponent::Known(h) => hole - h.len(),
+        }
+    }
+}
+
+/// Everything known about a hand: the game, hero's hole cards, the board, how each
+/// opponent is dealt, and dead (exposed or mucked) cards that nobody can receive.
+#[derive(Debug, Clone, Default)]
+pub struct Deal {
+    pub game: Game,
+    pub hero: Vec<Card>,
+    pub board: Vec<Card>,
+    pub opponents: Vec<Opponent>,
+    pub dead: Vec<Card>,
+}
+
+impl Deal {
+    /// A Hold'em deal without dead cards.
+    pub fn holdem(hero: &[Card], board: &[Card], opponents: &[Opponent]) -> Self {
+        Deal {
+            game: Game::Holdem,
+            hero: hero.to_vec(),
+            board: board.to_vec(),
+            opponents: opponents.to_vec(),
+            dead: Vec::new(),
+        }
+    }
+
+    /// Every card that cannot be dealt: hero, board, known opponent hands and dead cards.
+    pub fn known_cards(&self) -> Vec<Card> {
+        let mut known: Vec<Card> = self.hero.iter().chain(&self.board).chain(&self.dead).copied().collect();
+        for o in &self.opponents {
+            if let Opponent::Known(h) = o {
+                known.extend_from_slice(h);
+            }
+        }
+        known
+    }
+
+    /// Opponents with the `known` cards, and cards outside the game's deck, removed
+    /// from their ranges.
+    pub(crate) fn opponents_without(&self, known: &[Card]) -> Vec<Opponent> {
+        let mut removed = known.to_vec();
+        removed.extend(self.game.missing_cards());
+        self.opponents
+            .iter()
+            .map(|o| match o {
+                Opponent::Range(r) => Opponent::Range(r.without_cards(&removed)),
+                other => other.clone(),
+            })
+            .collect()
+    }
+
+    /// Hero's cards still to come (stud only; hero's hand is complete in other games).
+    pub fn hero_unknown_cards(&self) -> usize {
+        self.game.hole_cards() - self.hero.len()
+    }
+
+    /// Board cards still to come.
+    pub fn board_unknown_cards(&self) -> usize {
+        self.game.board_cards() - self.board.len()
+    }
+
+    /// The same deal with one more board card.
+    pub fn with_board_card(&self, card: Card) -> Deal {
+        let mut next = self.clone();
+        next.board.push(card);
+        next
+    }
+}
+
+/// Per-request trial description shared (read-only) by all workers: known cards are
+/// already removed from the deck template and from every range.
+struct TrialSetup {
+    game: Game,
+    /// Hole cards per player.
+    hole: usize,
+    /// Hero's known cards; `hero_missing` more are dealt each trial in stud.
+    hero: Vec<usize>,
+    hero_missing: usize,
+    board: Vec<usize>,
     need_board: usize,
-    rng: &mut impl FnMut() -> f64,
-    scratch: &mut [usize],
-    all_board: &mut [Card],
-    seven_buf: &mut [Card; 7],
-) -> u8 {
-    let n_draw = num_opponents * 2 + need_board;
-    draw_indices(deck, n_draw, rng, scratch);
-    let mut offset = 0;
-    for i in 0..need_board {
-        all_board[board_len + i] = Card::from_idx(scratch[offset + i]);
-    }
-    offset += need_board;
-    seven_buf[0] = hero[0];
-    seven_buf[1] = hero[1];
-    for (i, c) in all_board.iter().enumerate() {
-        seven_buf[2 + i] = *c;
-    }
-    let mut idx7 = [0usize; 7];
-    for (i, c) in seven_buf.iter().enumerate() {
-        idx7[i] = c.to_idx();
-    }
-    let hero_score = best_hand_score_7_indices(&idx7);
-    let mut best_opp = 0u32;
-    for _ in 0..num_opponents {
-        seven_buf[0] = Card::from_idx(scratch[offset]);
-        seven_buf[1] = Card::from_idx(scratch[offset + 1]);
-        offset += 2;
-        for (i, c) in all_board.iter().enumerate() {
-            seven_buf[2 + i] = *c;
-        }
-        for (i, c) in seven_buf.iter().enumerate() {
-            idx7[i] = c.to_idx();
-        }
-        let s = best_hand_score_7_indices(&idx7);
-        if s < best_opp || best_opp == 0 {
-            best_opp = s;
-        }
-    }
-    if hero_score < best_opp {
-        0
-    } else if hero_score > best_opp {
-        2
-    } else {
-        1
+    opponents: Vec<Opponent>,
+    /// Cards drawn per trial: board, hero's and every opponent's unknown cards.
+    n_draw: usize,
+    /// Cards left to deal once every known card is removed.
+    deck: CardSet,
+    /// For common random numbers: the game's whole deck, which every trial then shuffles
+    /// in the same order (see `CardSet::draw_shuffled`). None draws straight from the deck.
+    order: Option<Vec<usize>>,
+    strata: Option<Strata>,
+}
+
+/// Per-worker counters, merged once all workers finish. Counts are weights so the
+/// same tally serves Monte Carlo (weight 1 per trial) and exact enumeration.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Tally {
+    win: f64,
+    tie: f64,
+    lose: f64,
+    /// Hero's pot share per trial (1/k in a k-way chop) and its square, for the mean and variance.
+    equity: f64,
+    equity_sq: f64,
+    /// [win, tie, lose, pot share] per seat, seat 0 = hero.
+    seats: Vec<[f64; 4]>,
+    /// Final hand category of hero and of the best opponent, indexed like `HandCategory::ALL`.
+    hero_categories: [f64; 9],
+    opponent_categories: [f64; 9],
+    /// Hi-lo games only: [scoop, high only, low only, quartered, no low] for hero.
+    hi_lo: [f64; 5],
+    /// Stratified runs only: unweighted sums per stratum class, for the standard errors.
+    strata: Vec<ClassSums>,
+}
+
+impl Tally {
+    pub(crate) fn new(num_seats: usize) -> Self {
+        Tally {
+            seats: vec![[0.0; 4]; num_seats],
+            ..Default::default()
+        }
+    }
+
+    fn with_strata(mut self, classes: usize) -> Self {
+        self.strata = vec![[0.0; 5]; classes];
+        self
+    }
+
+    pub(crate) fn merge(mut self, other: Tally) -> Tally {
+        self.win += other.win;
+        self.tie += other.tie;
+        self.lose += other.lose;
+        self.equity += other.equity;
+        self.equity_sq += other.equity_sq;
+        for (a, b) in self.seats.iter_mut().zip(other.seats) {
+            for k in 0..4 {
+                a[k] += b[k];
+            }
+        }
+        for k in 0..9 {
+            self.hero_categories[k] += other.hero_categories[k];
+            self.opponent_categories[k] += other.opponent_categories[k];
+        }
+        for k in 0..5 {
+            self.hi_lo[k] += other.hi_lo[k];
+        }
+        if self.strata.len() < other.strata.len() {
+            self.strata.resize(other.strata.len(), [0.0; 5]);
+        }
+        for (a, b) in self.strata.iter_mut().zip(other.strata) {
+            for k in 0..5 {
+                a[k] += b[k];
+            }
+        }
+        self
+    }
+
+    /// Add one unweighted trial to a stratum class: hero's outcome against the best
+    /// opponent (0 win, 1 tie, 2 lose) and pot share.
+    fn record_stratum(&mut self, class: usize, outcome: usize, share: f64) {
+        let sums = &mut self.strata[class];
+        sums[0] += 1.0;
+        if outcome < 2 {
+            sums[1 + outcome] += 1.0;
+        }
+        sums[3] += share;
+        sums[4] += share * share;
+    }
+
+    /// Record one showdown. `scores[0]` is hero, lower is stronger. Returns hero's
+    /// outcome (0 win, 1 tie, 2 lose) and pot share.
+    pub(crate) fn record(&mut self, scores: &[u32], weight: f64) -> (usize, f64) {
+        let best = *scores.iter().min().unwrap();
+        let winners = scores.iter().filter(|&&s| s == best).count();
+        let share = 1.0 / winners as f64;
+        for (seat, &s) in scores.iter().enumerate() {
+            let k = if s != best {
+                2
+            } else if winners == 1 {
+                0
+            } else {
+                1
+            };
+            self.seats[seat][k] += weight;
+            if s == best {
+                self.seats[seat][3] += weight * share;
+            }
+        }
+        let best_opp = self.record_categories(scores, weight);
+        let hero_share = if scores[0] == best { share } else { 0.0 };
+        self.equity += weight * hero_share;
+        self.equity_sq += weight * hero_share * hero_share;
+        let outcome = if scores[0] < best_opp {
+            self.win += weight;
+            0
+        } else if scores[0] > best_opp {
+            self.lose += weight;
+            2
+        } else {
+            self.tie += weight;
+            1
+        };
+        (outcome, hero_share)
+    }
+
+    /// Count the final categories of hero and the best opponent; returns the latter's score.
+    fn record_categories(&mut self, scores: &[u32], weight: f64) -> u32 {
+        let best_opp = scores[1..].iter().copied().min().unwrap_or(u32::MAX);
+        self.hero_categories[HandCategory::from_score(scores[0]).index()] += weight;
+        if best_opp != u32::MAX {
+            self.opponent_categories[HandCategory::from_score(best_opp).index()] += weight;
+        }
+        best_opp
+    }
+
+    /// Record one hi-lo showdown: half the pot to the best high, half to the best
+    /// qualifying low (`u32::MAX` = no low), the high takes it all when nobody has a low.
+    /// A seat wins when it takes the whole pot, loses when it gets nothing, ties otherwise.
+    /// Returns hero's outcome and pot share like `record`.
+    pub(crate) fn record_hilo(&mut self, scores: &[u32], lows: &[u32], weight: f64) -> (usize, f64) {
+        let best_high = *scores.iter().min().unwrap();
+        let best_low = *lows.iter().min().unwrap();
+        let high_winners = scores.iter().filter(|&&s| s == best_high).count() as f64;
+        let low_winners = lows.iter().filter(|&&s| s == best_low).count() as f64;
+        let (high_pot, low_pot) = if best_low == u32::MAX { (1.0, 0.0) } else { (0.5, 0.5) };
+        let share_of = |seat: usize| {
+            let mut share = 0.0;
+            if scores[seat] == best_high {
+                share += high_pot / high_winners;
+            }
+            if low_pot > 0.0 && lows[seat] == best_low {
+                share += low_pot / low_winners;
+            }
+            share
+        };
+        for seat in 0..scores.len() {
+            let share = share_of(seat);
+            let k = if share >= 1.0 {
+                0
+            } else if share == 0.0 {
+                2
+            } else {
+                1
+            };
+            self.seats[seat][k] += weight;
+            self.seats[seat][3] += weight * share;
+        }
+        self.record_categories(scores, weight);
+
+        let share = share_of(0);
+        self.equity += weight * share;
+        self.equity_sq += weight * share * share;
+        let outcome = if share >= 1.0 {
+            self.win += weight;
+            0
+        } else if share == 0.0 {
+            self.lose += weight;
+            2
+        } else {
+            self.tie += weight;
+            1
+        };
+        let won_high = scores[0] == best_high;
+        let won_low = low_pot > 0.0 && lows[0] == best_low;
+        if share >= 1.0 {
+            self.hi_lo[0] += weight;
+        } else if won_high && !won_low {
+            self.hi_lo[1] += weight;
+        } else if won_low && !won_high {
+            self.hi_lo[2] += weight;
+        }
+        if share == 0.25 {
+            self.hi_lo[3] += weight;
+        }
+        if low_pot == 0.0 {
+            self.hi_lo[4] += weight;
+        }
+        (outcome, share)
+    }
+
+    pub(crate) fn total(&self) -> f64 {
+        self.win + self.tie + self.lose
+    }
+
+    /// Normalize into a result; `trials` is reported as given. `sampled` results get
+    /// binomial standard errors over `trials`, exact ones get zero. `hi_lo` is filled
+    /// when `game` splits the pot.
+    pub(crate) fn into_result(
+        self,
+        game: Game,
+        trials: u64,
+        elapsed_ms: u64,
+        sampled: bool,
+    ) -> SimResult {
+        let total = self.total();
+        let frac = |n: f64| if total == 0.0 { 0.0 } else { n / total };
+        let (win, tie, lose) = (frac(self.win), frac(self.tie), frac(self.lose));
+        let equity = frac(self.equity);
+        SimResult {
+            win,
+            tie,
+            lose,
+            equity,
+            std_err: if sampled {
+                StdErrors::sampled(win, tie, lose, equity, frac(self.equity_sq), trials)
+            } else {
+                StdErrors::default()
+            },
+            trials,
+            elapsed_ms,
+            seats: self
+                .seats
+                .iter()
+                .map(|s| SeatResult {
+                    win: frac(s[0]),
+                    tie: frac(s[1]),
+                    lose: frac(s[2]),
+                    equity: frac(s[3]),
+                })
+                .collect(),
+            hero_categories: self.hero_categories.map(frac),
+            opponent_categories: self.opponent_categories.map(frac),
+            hi_lo: game.is_hi_lo().then(|| {
+                let [scoop, high_only, low_only, quartered, no_low] = self.hi_lo.map(frac);
+                HiLoStats {
+                    scoop,
+                    high_only,
+                    low_only,
+                    quartered,
+                    no_low,
+                }
+            }),
+            stratified: false,
+        }
+    }
+}
+
+/// Per-worker scratch space reused across trials (no allocation in the hot loop).
+struct Buffers {
+    drawn: Vec<usize>,
+    /// Opponent hole cards, `hole` consecutive entries per opponent.
+    holes: Vec<usize>,
+    /// Hero's known cards followed by the ones dealt this trial.
+    hero: Vec<usize>,
+    scores: Vec<u32>,
+    /// 8-or-better low per seat, hi-lo games only.
+    lows: Vec<u32>,
+}
+
+impl Buffers {
+    fn new(setup: &TrialSetup) -> Self {
+        let mut hero = setup.hero.clone();
+        hero.resize(setup.hole, 0);
+        Buffers {
+            drawn: vec![0; setup.n_draw],
+            hero,
+            holes: vec![0; setup.opponents.len() * setup.hole],
+            scores: vec![0; setup.opponents.len() + 1],
+            lows: vec![u32::MAX; setup.opponents.len() + 1],
+        }
+    }
+}
+
+impl TrialSetup {
+    fn new(deal: &Deal) -> Self {
+        let known = deal.known_cards();
+        let opponents = deal.opponents_without(&known);
+        TrialSetup {
+            game: deal.game,
+            hole: deal.game.hole_cards(),
+            hero: deal.hero.iter().map(|c| c.to_idx()).collect(),
+            hero_missing: deal.hero_unknown_cards(),
+            board: deal.board.iter().map(|c| c.to_idx()).collect(),
+            need_board: deal.board_unknown_cards(),
+            n_draw: deal.board_unknown_cards()
+                + deal.hero_unknown_cards()
+                + opponents.iter().map(|o| o.unknown_cards(deal.game.hole_cards())).sum::<usize>(),
+            opponents,
+            deck: CardSet::from_indices(&deal.game.deck(&known)),
+            order: None,
+            strata: None,
+        }
+    }
+
+    /// Draw every trial against the whole deck's order, so deals run with the same seed
+    /// share their random cards (`simulate_compare`).
+    fn common_order(mut self, deal: &Deal) -> Self {
+        self.order = Some(deal.game.deck(&[]));
+        self
+    }
+
+    /// Stratify trials when `options` ask for it and the deal allows it, for batches of
+    /// at least `min_batch` trials.
+    fn stratified(mut self, deal: &Deal, options: &SimOptions, min_batch: u64) -> Self {
+        if options.stratify {
+            self.strata = Strata::build(deal, self.deck, min_batch);
+        }
+        self
+    }
+
+    fn num_seats(&self) -> usize {
+        self.opponents.len() + 1
+    }
+
+    fn new_tally(&self) -> Tally {
+        Tally::new(self.num_seats()).with_strata(self.strata.as_ref().map_or(0, Strata::num_classes))
+    }
+
+    /// Standard errors of a sampled tally: stratified when possible, binomial otherwise.
+    fn std_errors(&self, tally: &Tally) -> StdErrors {
+        if let Some(se) = self.strata.as_ref().and_then(|s| s.std_errors(&tally.strata)) {
+            return se;
+        }
+        let done = tally.total();
+        if done == 0.0 {
+            return StdErrors::default();
+        }
+        let frac = |x: f64| x / done;
+        let (win, tie, lose) = (frac(tally.win), frac(tally.tie), frac(tally.lose));
+        StdErrors::sampled(win, tie, lose, frac(tally.equity), frac(tally.equity_sq), done as u64)
+    }
+
+    /// Normalize a sampled tally into a result.
+    fn finish(&self, tally: Tally, elapsed_ms: u64) -> SimResult {
+        let std_err = self.std_errors(&tally);
+        let done = tally.total().round() as u64;
+        let mut result = tally.into_result(self.game, done, elapsed_ms, true);
+        result.std_err = std_err;
+        result.stratified = self.strata.is_some();
+        result
+    }
+
+    /// False if some range has no combo left after removing known cards.
+    fn dealable(&self) -> bool {
+        self.opponents
+            .iter()
+            .all(|o| !matches!(o, Opponent::Range(r) if r.is_empty()))
+    }
+
+    /// Run `trials` as blocks of `BLOCK_TRIALS`, spread over the rayon workers. Block `i`
+    /// uses RNG stream `first_block + i` and block tallies are merged in block order, so a
+    /// seeded batch gives bit-identical results whatever the thread count. Trial `t` of the
+    /// batch is trial `first_block * BLOCK_TRIALS + t` overall, which picks its stratum.
+    fn run_batch(&self, trials: u64, rng: RngConfig, first_block: u64) -> Tally {
+        if !self.dealable() {
+            return self.new_tally();
+        }
+        let seed = rng.batch_seed();
+        let first = first_block * BLOCK_TRIALS;
+        let weights = self.strata.as_ref().map(|s| s.class_weights(first, trials));
+        let blocks: Vec<Tally> = (0..trials.div_ceil(BLOCK_TRIALS))
+            .into_par_iter()
+            .map_init(
+                || Buffers::new(self),
+                |buf, block| {
+                    let mut rng = SimRng::new(rng.algorithm, seed, first_block + block);
+                    let mut tally = self.new_tally();
+                    for t in block * BLOCK_TRIALS..((block + 1) * BLOCK_TRIALS).min(trials) {
+                        let stratum = self.strata.as_ref().zip(weights.as_deref()).map(|(s, w)| s.get(first + t, w));
+                        self.run(&mut rng, buf, &mut tally, stratum);
+                    }
+                    tally
+                },
+            )
+            .collect();
+        blocks.into_iter().fold(self.new_tally(), Tally::merge)
+    }
+
+    /// Fill known holes and deal every ranged opponent at once, rejecting whole deals
+    /// that collide. Returns the range-dealt cards, or None if no compatible deal was found.
+    fn deal_fixed(&self, holes: &mut [usize], rng: &mut impl UniformRng) -> Option<CardSet> {
+        'attempt: for _ in 0..MAX_DEAL_ATTEMPTS {
+            let mut used = CardSet::EMPTY;
+            for (seat, opp) in self.opponents.iter().enumerate() {
+                match opp {
+                    Opponent::Range(r) => {
+                        let combo = r.sample(rng);
+                        let m = CardSet::from_indices(&combo);
+                        if !used.is_disjoint(m) {
+                            continue 'attempt;
+                        }
+                        used = used | m;
+                        holes[seat * 2..seat * 2 + 2].copy_from_slice(&combo);
+                    }
+                    Opponent::Known(h) => {
+                        for (slot, c) in holes[seat * self.hole..].iter_mut().zip(h) {
+                            *slot = c.to_idx();
+                        }
+                    }
+                    Opponent::Random => {}
+                }
+            }
+            return Some(used);
+        }
+        None
     }
+
+    /// One trial, recorded into `tally`, with the first board cards taken from `stratum`
+    /// if given. Returns false when ranged opponents could not be dealt.
+    fn run(
+        &self,
+        rng: &mut impl UniformRng,
+        buf: &mut Buffers,
+        tally: &mut Tally,
+        stratum: Option<Stratum>,
+    ) -> bool {
+        let Buffers {
+            drawn: scratch,
+            holes,
+            hero,
+            scores,
+            lows,
+        } = buf;
+        let Some(used) = self.deal_fixed(holes, rng) else {
+            return false;
+        };
+        let fixed = stratum.map_or(&[][..], |s| s.cards);
+        scratch[..fixed.len()].copy_from_slice(fixed);
+        let rest = self.deck - used - CardSet::from_indices(fixed);
+        match &self.order {
+            Some(order) => rest.draw_shuffled(order, rng, &mut scratch[fixed.len()..]),
+            None => rest.draw_into(rng, &mut scratch[fixed.len()..]),
+        }
+        let k = self.hole;
+        let mut full_board = [0usize; 5];
+        let n_board = self.board.len();
+        full_board[..n_board].copy_from_slice(&self.board);
+        full_board[n_board..n_board + self.need_board].copy_from_slice(&scratch[..self.need_board]);
+        let board = &full_board[..self.game.board_cards()];
+        let mut offset = self.need_board;
+        hero[k - self.hero_missing..].copy_from_slice(&scratch[offset..offset + self.hero_missing]);
+        offset += self.hero_missing;
+        for (seat, opp) in self.opponents.iter().enumerate() {
+            let m = opp.unknown_cards(k);
+            holes[(seat + 1) * k - m..(seat + 1) * k].copy_from_slice(&scratch[offset..offset + m]);
+            offset += m;
+        }
+        scores[0] = self.game.score(hero, board);
+        for (seat, hole) in holes.chunks_exact(k).enumerate() {
+            scores[seat + 1] = self.game.score(hole, board);
+        }
+        let weight = stratum.map_or(1.0, |s| s.weight);
+        let (outcome, share) = if self.game.is_hi_lo() {
+            lows[0] = self.game.low_score(hero, board);
+            for (seat, hole) in holes.chunks_exact(k).enumerate() {
+                lows[seat + 1] = self.game.low_score(hole, board);
+            }
+            tally.record_hilo(scores, lows, weight)
+        } else {
+            tally.record(scores, weight)
+        };
+        if let Some(s) = stratum {
+            tally.record_stratum(s.class, outcome, share);
+        }
+        true
+    }
+}
+
+/// Win/tie/lose frequencies for one seat. Tie means sharing the best hand.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SeatResult {
+    pub win: f64,
+    pub tie: f64,
+    pub lose: f64,
+    /// Expected share of the pot; summed over all seats this is 1.
+    pub equity: f64,
+}
+
+/// Standard errors of the reported frequencies and of equity (0 when exact).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct StdErrors {
+    pub win: f64,
+    pub tie: f64,
+    pub lose: f64,
+    pub equity: f64,
 }
 
+/// 95% confidence intervals as [low, high], clamped to [0, 1].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ci95 {
+    pub win: [f64; 2],
+    pub tie: [f64; 2],
+    pub lose: [f64; 2],
+    pub equity: [f64; 2],
+}
+
+impl Ci95 {
+    pub fn map(self, f: impl Fn(f64) -> f64) -> Self {
+        Ci95 {
+            win: self.win.map(&f),
+            tie: self.tie.map(&f),
+            lose: self.lose.map(&f),
+            equity: self.equity.map(&f),
+        }
+    }
+}
+
+impl StdErrors {
+    /// Errors of frequencies estimated from `n` independent trials. `equity` and
+    /// `equity_sq` are the means of the per-trial pot share and of its square, so the
+    /// share's variance is `equity_sq - equity^2`.
+    pub fn sampled(win: f64, tie: f64, lose: f64, equity: f64, equity_sq: f64, n: u64) -> Self {
+        if n == 0 {
+            return StdErrors::default();
+        }
+        let n = n as f64;
+        let se = |p: f64| (p * (1.0 - p) / n).sqrt();
+        let eq_var = (equity_sq - equity * equity).max(0.0);
+        StdErrors {
+            win: se(win),
+            tie: se(tie),
+            lose: se(lose),
+            equity: (eq_var / n).sqrt(),
+        }
+    }
+
+    pub fn map(self, f: impl Fn(f64) -> f64) -> Self {
+        StdErrors {
+            win: f(self.win),
+            tie: f(self.tie),
+            lose: f(self.lose),
+            equity: f(self.equity),
+        }
+    }
+
+    /// Normal-approximation 95% intervals around the given point estimates.
+    pub fn ci95(&self, win: f64, tie: f64, lose: f64, equity: f64) -> Ci95 {
+        let ci = |p: f64, se: f64| [(p - 1.96 * se).max(0.0), (p + 1.96 * se).min(1.0)];
+        Ci95 {
+            win: ci(win, self.win),
+            tie: ci(tie, self.tie),
+            lose: ci(lose, self.lose),
+            equity: ci(equity, self.equity),
+        }
+    }
+}
+
+/// How hero's pot share came about in a hi-lo game, as fractions of trials.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HiLoStats {
+    /// Whole pot: both halves, or the high when no low qualifies.
+    pub scoop: f64,
+    /// Some of the high half and none of the low half.
+    pub high_only: f64,
+    /// Some of the low half and none of the high half.
+    pub low_only: f64,
+    /// Exactly a quarter of the pot, e.g. sharing one half two ways.
+    pub quartered: f64,
+    /// No player made a qualifying low.
+    pub no_low: f64,
+}
+
+#[derive(Debug, Clone)]
 pub struct SimResult {
     pub win: f64,
     pub tie: f64,
     pub lose: f64,
+    /// Hero's expected share of the pot: a k-way chop counts 1/k.
+    pub equity: f64,
+    pub std_err: StdErrors,
     pub trials: u64,
     pub elapsed_ms: u64,
+    /// Per-seat results: index 0 is hero, then opponents in the order given.
+    pub seats: Vec<SeatResult>,
+    /// How often hero / the best opponent finishes with each category, indexed like
+    /// `HandCategory::ALL` (strongest first). Each sums to 1 over completed trials.
+    pub hero_categories: [f64; 9],
+    pub opponent_categories: [f64; 9],
+    /// Scoop / split breakdown, hi-lo games only.
+    pub hi_lo: Option<HiLoStats>,
+    /// Trials were stratified over the next board cards (see `SimOptions::stratify`).
+    pub stratified: bool,
+}
+
+impl SimResult {
+    pub fn ci95(&self) -> Ci95 {
+        self.std_err.ci95(self.win, self.tie, self.lose, self.equity)
+    }
+}
+
+/// How a simulation draws its trials. Converts from a seed (`Some(seed)` / `None`) or an
+/// `RngConfig`, leaving variance reduction off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimOptions {
+    pub rng: RngConfig,
+    /// Stratified sampling: trials cycle through the next one or two board cards instead of
+    /// drawing them, one representative per suit-isomorphism class weighted by the class's
+    /// size. Unbiased, and usually less noisy on flop and turn spots (little gain preflop).
+    /// It only removes board noise: with random opponents the standard error stays well
+    /// above that of a plain run with 20x the trials. The standard error is estimated
+    /// class by class, so with only a few trials per class it is itself rough and may come
+    /// out above the plain one. Skipped when a ranged opponent is dealt or no board card is
+    /// to come. There is no antithetic sampling.
+    pub stratify: bool,
+}
+
+impl From<RngConfig> for SimOptions {
+    fn from(rng: RngConfig) -> Self {
+        SimOptions { rng, stratify: false }
+    }
+}
+
+impl From<Option<u64>> for SimOptions {
+    fn from(seed: Option<u64>) -> Self {
+        RngConfig::from(seed).into()
+    }
 }
 
 pub fn simulate(
@@ -113,64 +765,111 @@ pub fn simulate(
     hero: &[Card],
     board: &[Card],
     trials: u64,
-    seed: Option<u64>,
+    options: impl Into<SimOptions>,
 ) -> SimResult {
-    let num_opponents = (players as usize).saturating_sub(1);
-    let need_board = 5 - board.len();
-    let known: Vec<Card> = hero.iter().chain(board.iter()).cloned().collect();
-    let deck_template = build_deck(&known);
+    let opponents: Vec<Opponent> = (1..players).map(|_| Opponent::Random).collect();
+    simulate_deal(&Deal::holdem(hero, board, &opponents), trials, options)
+}
+
+/// Like `simulate` for any `Deal`: game variant, each opponent described individually
+/// (random, range or known) and dead cards. `trials` in the result counts completed
+/// trials: a trial whose ranged opponents cannot be dealt without card collisions is skipped.
+pub fn simulate_deal(deal: &Deal, trials: u64, options: impl Into<SimOptions>) -> SimResult {
+    let options = options.into();
+    let start = Instant::now();
+    let setup = TrialSetup::new(deal).stratified(deal, &options, trials);
+    let tally = setup.run_batch(trials, options.rng, 0);
+    setup.finish(tally, start.elapsed().as_millis() as u64)
+}
+
+/// Simulate several deals (typically different hero hands in the same spot) with common
+/// random numbers: one seed for all, and every trial shuffles the same deck order, so
+/// board and opponent cards coincide across deals except where a deal's known cards
+/// are involved. Differences between the results are then much less noisy than with
+/// independent runs. Stratification is not used, since strata differ between deals.
+pub fn simulate_compare(deals: &[Deal], trials: u64, options: impl Into<SimOptions>) -> Vec<SimResult> {
+    let options = options.into();
+    let rng = RngConfig {
+        seed: Some(options.rng.batch_seed()),
+        ..options.rng
+    };
+    deals
+        .iter()
+        .map(|deal| {
+            let start = Instant::now();
+            let setup = TrialSetup::new(deal).common_order(deal);
+            let tally = setup.run_batch(trials, rng, 0);
+            setup.finish(tally, start.elapsed().as_millis() as u64)
+        })
+        .collect()
+}
+
+/// Trials per adaptive round, split across workers like a regular batch.
+const ADAPTIVE_ROUND_TRIALS: u64 = 10_000;
+/// Hard cap on adaptive runs, whatever the stop rule says.
+pub const ADAPTIVE_MAX_TRIALS: u64 = 10_000_000;
+
+/// When an adaptive run stops: first of target equity standard error, deadline, or
+/// `max_trials` reached. With neither target nor deadline it runs `max_trials`.
+#[derive(Debug, Clone, Copy)]
+pub struct StopRule {
+    pub target_std_err: Option<f64>,
+    pub deadline: Option<std::time::Duration>,
+    pub max_trials: u64,
+}
+
+/// Why an adaptive run stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    Precision,
+    Deadline,
+    MaxTrials,
+    /// The progress callback asked to stop.
+    Cancelled,
+}
+
+/// Run rounds of trials until `rule` is met. Each round continues with the next RNG
+/// streams, so a seeded run that stops on precision or `max_trials` is reproducible on
+/// any thread count. `trials` in the result is the number of completed trials actually used.
+pub fn simulate_adaptive(deal: &Deal, rule: StopRule, options: impl Into<SimOptions>) -> (SimResult, StopReason) {
+    simulate_with_progress(deal, rule, options, |_| true)
+}
+
+/// `simulate_adaptive` reporting the running result after every round to `progress`,
+/// which returns false to cancel (the result so far is returned with `Cancelled`).
+pub fn simulate_with_progress(
+    deal: &Deal,
+    rule: StopRule,
+    options: impl Into<SimOptions>,
+    mut progress: impl FnMut(&SimResult) -> bool,
+) -> (SimResult, StopReason) {
+    let options = options.into();
     let start = Instant::now();
-    let win = AtomicU64::new(0);
-    let tie = AtomicU64::new(0);
-    let lose = AtomicU64::new(0);
-    let num_workers = rayon::current_num_threads();
-    let chunk = (trials as usize + num_workers - 1) / num_workers;
-    (0..num_workers).into_par_iter().for_each(|worker_id| {
-        let mut rng = WorkerRng::new(seed, worker_id);
-        let n_draw = num_opponents * 2 + need_board;
-        let mut scratch = vec![0usize; n_draw];
-        let mut all_board: Vec<Card> = board.to_vec();
-        all_board.resize(5, Card { rank: 0, suit: 0 });
-        let deck_template = deck_template.clone();
-        let mut deck = deck_template.clone();
-        let mut seven_buf = [Card { rank: 0, suit: 0 }; 7];
-        let start_idx = worker_id * chunk;
-        let end_idx = (start_idx + chunk).min(trials as usize);
-        let mut lwin = 0u64;
-        let mut ltie = 0u64;
-        let mut llose = 0u64;
-        for _ in start_idx..end_idx {
-            deck.copy_from_slice(&deck_template);
-            let res = run_one_trial(
-                hero,
-                board.len(),
-                &mut deck,
-                num_opponents,
-                need_board,
-                &mut || rng.next_f64(),
-                &mut scratch,
-                &mut all_board,
-                &mut seven_buf,
-            );
-            match res {
-                0 => lwin += 1,
-                1 => ltie += 1,
-                _ => llose += 1,
+    let max_trials = rule.max_trials.min(ADAPTIVE_MAX_TRIALS);
+    let setup = TrialSetup::new(deal).stratified(deal, &options, ADAPTIVE_ROUND_TRIALS.min(max_trials));
+    let mut tally = setup.new_tally();
+    let mut attempted = 0u64;
+    let mut next_block = 0u64;
+    let reason = loop {
+        if attempted >= max_trials || !setup.dealable() {
+            break StopReason::MaxTrials;
+        }
+        let n = ADAPTIVE_ROUND_TRIALS.min(max_trials - attempted);
+        tally = tally.merge(setup.run_batch(n, options.rng, next_block));
+        attempted += n;
+        next_block += n.div_ceil(BLOCK_TRIALS);
+        if let Some(target) = rule.target_std_err {
+            if tally.total() > 0.0 && setup.std_errors(&tally).equity <= target {
+                break StopReason::Precision;
             }
         }
-        win.fetch_add(lwin, Ordering::Relaxed);
-        tie.fetch_add(ltie, Ordering::Relaxed);
-        lose.fetch_add(llose, Ordering::Relaxed);
-    });
-    let elapsed = start.elapsed();
-    let w = win.load(Ordering::Relaxed) as f64 / trials as f64;
-    let t = tie.load(Ordering::Relaxed) as f64 / trials as f64;
-    let l = lose.load(Ordering::Relaxed) as f64 / trials as f64;
-    SimResult {
-        win: w,
-        tie: t,
-        lose: l,
-        trials,
-        elapsed_ms: elapsed.as_millis() as u64,
-    }
+        if rule.deadline.is_some_and(|d| start.elapsed() >= d) {
+            break StopReason::Deadline;
+        }
+        if !progress(&setup.finish(tally.clone(), start.elapsed().as_millis() as u64)) {
+            break StopReason::Cancelled;
+        }
+    };
+    (setup.finish(tally, start.elapsed().as_millis() as u64), reason)
 }
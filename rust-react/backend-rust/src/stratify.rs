@@ -0,0 +1,235 @@
+// Stratified sampling for Monte Carlo: the first one or two unknown board cards are
+// fixed per trial instead of drawn, so each runout "texture" gets its exact share of
+// the estimate instead of a random one. Choices that are the same up to a suit
+// permutation fixing every known card (e.g. hearts <-> clubs when neither appears
+// anywhere) deal the same odds, so each such class is sampled through one canonical
+// representative and weighted by the size of its orbit.
+
+use crate::cards::CardSet;
+use crate::simulate::{Deal, Opponent, StdErrors};
+use std::collections::BTreeMap;
+
+/// Strata for one deal, one per suit-isomorphism class: class `t % len` is used by trial `t`.
+#[derive(Debug, Clone)]
+pub(crate) struct Strata {
+    /// Board cards fixed by each class's representative (`per_stratum` of them).
+    cards: Vec<[usize; 2]>,
+    per_stratum: usize,
+    /// Probability of each class: orbit size / number of card choices.
+    class_prob: Vec<f64>,
+}
+
+/// The stratum of one trial: cards to place first on the board, the class to record
+/// into and the trial's weight.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Stratum<'a> {
+    pub cards: &'a [usize],
+    pub class: usize,
+    pub weight: f64,
+}
+
+/// Per-class sums [trials, wins, ties, pot share, pot share squared], unweighted.
+pub(crate) type ClassSums = [f64; 5];
+
+impl Strata {
+    /// Strata over the first unknown board cards of `deal`, drawn from `deck`, with at
+    /// least two trials per class in a batch of `min_batch`: two cards when their classes
+    /// fit, else one. None when there is no board to come or a ranged opponent makes the
+    /// dealing order matter.
+    pub(crate) fn build(deal: &Deal, deck: CardSet, min_batch: u64) -> Option<Strata> {
+        let need = deal.board_unknown_cards();
+        if need == 0 || deal.opponents.iter().any(|o| matches!(o, Opponent::Range(_))) {
+            return None;
+        }
+        let cards: Vec<usize> = deck.indices().collect();
+        let symmetries = suit_symmetries(deal);
+        [2, 1].into_iter().filter(|&k| k <= need).find_map(|per_stratum| {
+            let (cards, class_prob) = classes(&cards, per_stratum, &symmetries);
+            (2 * cards.len() as u64 <= min_batch).then_some(Strata {
+                cards,
+                per_stratum,
+                class_prob,
+            })
+        })
+    }
+
+    pub(crate) fn num_classes(&self) -> usize {
+        self.class_prob.len()
+    }
+
+    /// Weight of a trial in each class for the batch `first..first + trials`: the class
+    /// probability over its share of the batch, so weighted sums estimate the population.
+    pub(crate) fn class_weights(&self, first: u64, trials: u64) -> Vec<f64> {
+        let len = self.num_classes() as u64;
+        (0..len)
+            .zip(&self.class_prob)
+            .map(|(c, &p)| {
+                // the partial cycle covers `trials % len` classes starting at `first % len`
+                let offset = (c + len - first % len) % len;
+                let n = trials / len + u64::from(offset < trials % len);
+                if n == 0 {
+                    0.0
+                } else {
+                    p * trials as f64 / n as f64
+                }
+            })
+            .collect()
+    }
+
+    /// Stratum of global trial `t`, with the batch's `weights` from `class_weights`.
+    pub(crate) fn get<'a>(&'a self, t: u64, weights: &[f64]) -> Stratum<'a> {
+        let class = (t % self.num_classes() as u64) as usize;
+        Stratum {
+            cards: &self.cards[class][..self.per_stratum],
+            class,
+            weight: weights[class],
+        }
+    }
+
+    /// Stratified standard errors, sum over classes of p² σ² / n. None unless every class
+    /// has at least two trials.
+    pub(crate) fn std_errors(&self, sums: &[ClassSums]) -> Option<StdErrors> {
+        if sums.len() != self.num_classes() || sums.iter().any(|s| s[0] < 2.0) {
+            return None;
+        }
+        let mut var = [0.0f64; 4];
+        for (s, &p) in sums.iter().zip(&self.class_prob) {
+            let n = s[0];
+            let bessel = n / (n - 1.0);
+            let bernoulli = |x: f64| (x / n) * (1.0 - x / n) * bessel;
+            let mean = s[3] / n;
+            let eq_var = (s[4] / n - mean * mean).max(0.0) * bessel;
+            for (v, x) in var.iter_mut().zip([
+                bernoulli(s[1]),
+                bernoulli(s[2]),
+                bernoulli(n - s[1] - s[2]),
+                eq_var,
+            ]) {
+                *v += p * p * x / n;
+            }
+        }
+        Some(StdErrors {
+            win: var[0].sqrt(),
+            tie: var[1].sqrt(),
+            lose: var[2].sqrt(),
+            equity: var[3].sqrt(),
+        })
+    }
+}
+
+/// The suit-isomorphism classes of the `k`-card choices (1 or 2) from `cards`: each
+/// class's canonical representative and its share of all choices.
+fn classes(cards: &[usize], k: usize, symmetries: &[[usize; 4]]) -> (Vec<[usize; 2]>, Vec<f64>) {
+    let mut orbits: BTreeMap<[usize; 2], f64> = BTreeMap::new();
+    let mut total = 0.0;
+    for (i, &a) in cards.iter().enumerate() {
+        let others = if k == 1 { &cards[i..=i] } else { &cards[i + 1..] };
+        for &b in others {
+            *orbits.entry(canonical(symmetries, [a, b])).or_default() += 1.0;
+            total += 1.0;
+        }
+    }
+    orbits.into_iter().map(|(rep, n)| (rep, n / total)).unzip()
+}
+
+/// Least image of a card choice under `symmetries` (which include the identity).
+fn canonical(symmetries: &[[usize; 4]], choice: [usize; 2]) -> [usize; 2] {
+    symmetries
+        .iter()
+        .map(|perm| {
+            let (a, b) = (permute(perm, choice[0]), permute(perm, choice[1]));
+            [a.min(b), a.max(b)]
+        })
+        .min()
+        .unwrap()
+}
+
+/// Card index with its suit relabelled by `perm`.
+fn permute(perm: &[usize; 4], idx: usize) -> usize {
+    perm[idx / 13] * 13 + idx % 13
+}
+
+/// Suit permutations that map every known card group (hero, board, dead, each known
+/// opponent) onto itself: suits may only trade places with suits holding the same
+/// ranks in every group.
+fn suit_symmetries(deal: &Deal) -> Vec<[usize; 4]> {
+    let mut groups: Vec<&[crate::cards::Card]> = vec![&deal.hero, &deal.board, &deal.dead];
+    for o in &deal.opponents {
+        if let Opponent::Known(h) = o {
+            groups.push(h);
+        }
+    }
+    let signature = |suit: usize| -> Vec<u16> {
+        groups
+            .iter()
+            .map(|g| {
+                g.iter()
+                    .filter(|c| c.suit as usize == suit)
+                    .fold(0u16, |m, c| m | 1 << (c.rank as usize))
+            })
+            .collect()
+    };
+    let sigs: Vec<Vec<u16>> = (0..4).map(signature).collect();
+    let mut perms = Vec::new();
+    for a in 0..4 {
+        for b in 0..4 {
+            for c in 0..4 {
+                for d in 0..4 {
+                    let perm = [a, b, c, d];
+                    let distinct = a != b && a != c && a != d && b != c && b != d && c != d;
+                    if distinct && (0..4).all(|s| sigs[perm[s]] == sigs[s]) {
+                        perms.push(perm);
+                    }
+                }
+            }
+        }
+    }
+    perms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse_cards;
+
+    #[test]
+    fn test_strata_classes_and_weights() {
+        // AsKd preflop: hearts and clubs are interchangeable, spades and diamonds are not.
+        let hero = parse_cards("As Kd").unwrap();
+        let deal = Deal::holdem(&hero, &[], &[Opponent::Random]);
+        let deck = CardSet::FULL - hero.iter().collect();
+        let strata = Strata::build(&deal, deck, 1_000_000).unwrap();
+        assert_eq!(strata.per_stratum, 2);
+        let choices = 50 * 49 / 2u64;
+        assert!((strata.num_classes() as u64) < choices);
+        assert!((strata.class_prob.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        // 2h3c and 2c3h are one class of two choices, 2h2c is its own image
+        let symmetries = suit_symmetries(&deal);
+        let idx = |c: &str| parse_cards(c).unwrap()[0].to_idx();
+        let prob = |a: &str, b: &str| {
+            let rep = canonical(&symmetries, [idx(a), idx(b)]);
+            strata.class_prob[strata.cards.iter().position(|s| *s == rep).unwrap()] * choices as f64
+        };
+        assert_eq!(canonical(&symmetries, [idx("2h"), idx("3c")]), canonical(&symmetries, [idx("3h"), idx("2c")]));
+        assert_ne!(canonical(&symmetries, [idx("2s"), idx("3c")]), canonical(&symmetries, [idx("2d"), idx("3c")]));
+        assert!((prob("2h", "3c") - 2.0).abs() < 1e-9);
+        assert!((prob("2h", "2c") - 1.0).abs() < 1e-9);
+
+        // Weighted trials sum to the batch size, whatever the batch's offset.
+        for (first, trials) in [(0, 5_000), (1_234, 7_777)] {
+            let w = strata.class_weights(first, trials);
+            let total: f64 = (first..first + trials).map(|t| strata.get(t, &w).weight).sum();
+            assert!((total - trials as f64).abs() < 1e-6, "{}", total);
+        }
+
+        // The batch only needs two trials per class, not per choice; too small a batch
+        // for pairs falls back to single cards, then to none.
+        let pairs = 2 * strata.num_classes() as u64;
+        assert!(pairs < 2 * choices);
+        assert_eq!(Strata::build(&deal, deck, pairs).unwrap().per_stratum, 2);
+        let singles = Strata::build(&deal, deck, pairs - 1).unwrap();
+        assert_eq!(singles.per_stratum, 1);
+        assert!(singles.num_classes() < 50);
+        assert!(Strata::build(&deal, deck, 2 * singles.num_classes() as u64 - 1).is_none());
+    }
+}
@@ -0,0 +1,63 @@
+// Known 7-card showdowns: (board, hand A, hand B, expected result for A).
+use std::cmp::Ordering;
+use texas_equity_api::cards::parse_cards;
+use texas_equity_api::evaluate::{best_hand_score_7, best_hand_score_7_indices, best_hand_score_7_reference};
+
+const SHOWDOWNS: &[(&str, &str, &str, Ordering)] = &[
+    // kicker decides a shared pair
+    ("Kh 7d 2c 9s 4h", "Ks Qd", "Kd Jc", Ordering::Greater),
+    // higher pair beats lower pair with an ace kicker
+    ("Kh 7d 2c 9s 4h", "Kc 3d", "2d Ac", Ordering::Greater),
+    // board plays: both hands tie on a board straight
+    ("5h 6d 7c 8s 9h", "2c 3c", "2d 3d", Ordering::Equal),
+    // wheel loses to six-high straight
+    ("3h 4d 5c Ks Qh", "Ah 2d", "6s 2c", Ordering::Less),
+    // wheel beats three of a kind
+    ("3h 4d 5c 5s Qh", "Ah 2d", "5d 9c", Ordering::Greater),
+    // counterfeited two pair: best two pairs plus kicker
+    ("Qh Qd 8c 8s 3h", "Ah 2d", "Kc 3c", Ordering::Greater),
+    // higher flush card wins
+    ("Ah 9h 5h 2c 3d", "Kh 4h", "Qh Jh", Ordering::Greater),
+    // full house: bigger trips win
+    ("Th Td 7c 7s 2h", "Tc 3d", "7d 2d", Ordering::Greater),
+    // quads on board: kicker plays
+    ("9h 9d 9c 9s 2h", "Ac 3d", "Kd Qh", Ordering::Greater),
+    // straight flush over quads
+    ("9h Th Jh 9c 9s", "Qh 8h", "9d 2c", Ordering::Greater),
+    // ace-high straight flush (royal) over king-high
+    ("Th Jh Qh Kh 2c", "Ah 3c", "9h 3d", Ordering::Greater),
+    // two pair with same top pair: second pair decides
+    ("Ah Ad 7c 4s 2h", "7d 3c", "4d Kc", Ordering::Greater),
+    // high card all the way down to the fifth card
+    ("Ah Jd 8c 6s 2h", "4d 3c", "5c 3d", Ordering::Less),
+    // six-card straight uses the top five
+    ("4h 5d 6c 7s 8h", "9c 2d", "3d 2c", Ordering::Greater),
+];
+
+fn score(board: &str, hand: &str) -> u32 {
+    let cards = parse_cards(&format!("{} {}", hand, board)).unwrap();
+    best_hand_score_7(&cards)
+}
+
+#[test]
+fn test_known_showdowns() {
+    for (board, a, b, expected) in SHOWDOWNS {
+        // lower score = stronger, so A's result is the reverse of the score order
+        let got = score(board, b).cmp(&score(board, a));
+        assert_eq!(got, *expected, "board {} : {} vs {}", board, a, b);
+    }
+}
+
+#[test]
+fn test_showdowns_agree_with_reference() {
+    for (board, a, b, _) in SHOWDOWNS {
+        for hand in [a, b] {
+            let cards = parse_cards(&format!("{} {}", hand, board)).unwrap();
+            let mut idx = [0usize; 7];
+            for (i, c) in cards.iter().enumerate() {
+                idx[i] = c.to_idx();
+            }
+            assert_eq!(best_hand_score_7_indices(&idx), best_hand_score_7_reference(&idx));
+        }
+    }
+}
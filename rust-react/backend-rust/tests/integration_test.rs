@@ -1,18 +1,564 @@
-use texas_equity_api::cards::{parse_card, validate_input};
+use texas_equity_api::cards::{parse_card, parse_card_list, parse_cards, validate_input, Card, CardSet, Hand, Rank, Suit};
+use texas_equity_api::game::Game;
 
 #[test]
 fn test_parse_card() {
     let c = parse_card("As").unwrap();
-    assert_eq!(c.rank, 12);
-    assert_eq!(c.suit, 0);
+    assert_eq!(c.rank, Rank::Ace);
+    assert_eq!(c.suit, Suit::Spades);
+    assert_eq!(c.to_idx(), 12);
     assert!(parse_card("1s").is_err());
+    assert_eq!("10h".parse::<Card>().unwrap(), Card::new(Rank::Ten, Suit::Hearts));
+    assert_eq!(" kD ".parse::<Card>().unwrap().to_string(), "Kd");
+    assert_eq!("Ax".parse::<Card>().unwrap_err().error, "invalid_suit");
+    for idx in 0..52 {
+        let c = Card::from_idx(idx);
+        assert_eq!(c.to_string().parse::<Card>().unwrap(), c);
+    }
+    assert!(Card::new(Rank::Ace, Suit::Clubs) > Card::new(Rank::King, Suit::Spades));
+}
+
+#[test]
+fn test_parse_flexible_cards() {
+    let ak = vec![Card::new(Rank::Ace, Suit::Spades), Card::new(Rank::King, Suit::Diamonds)];
+    for s in ["As Kd", "AsKd", "A♠K♦", "A♤ K♢", "as,kd", "As; Kd", "A♠️K♦️"] {
+        assert_eq!(parse_cards(s).unwrap(), ak, "{}", s);
+    }
+    assert_eq!(parse_cards("10h9h").unwrap().len(), 2);
+    assert_eq!("tc".parse::<Card>().unwrap(), Card::new(Rank::Ten, Suit::Clubs));
+    assert_eq!(parse_card_list(&["AsKd", "7h"]).unwrap().len(), 3);
+    assert!(parse_cards("").unwrap().is_empty());
+
+    let err = parse_cards("AsKx").unwrap_err();
+    assert_eq!((err.error.as_str(), err.position), ("invalid_suit", Some(3)));
+    let err = parse_cards("As Zd").unwrap_err();
+    assert_eq!((err.error.as_str(), err.position), ("invalid_rank", Some(3)));
+    let err = parse_cards("A♠K").unwrap_err();
+    assert_eq!((err.error.as_str(), err.position), ("invalid_card_length", Some(3)));
+    assert_eq!("AsKd".parse::<Card>().unwrap_err().error, "invalid_card_length");
+}
+
+#[test]
+fn test_card_set() {
+    let hand: CardSet = parse_cards("As Kd 2c").unwrap().iter().collect();
+    assert_eq!(hand.len(), 3);
+    assert!(hand.contains(parse_card("Kd").unwrap()));
+    assert_eq!(hand.iter().map(|c| c.to_string()).collect::<Vec<_>>(), ["As", "Kd", "2c"]);
+    assert_eq!(CardSet::FULL.len(), 52);
+    assert_eq!(CardSet::deck(Rank::Six).len(), 36);
+    let rest = CardSet::FULL - hand;
+    assert_eq!(rest.len(), 49);
+    assert!(rest.is_disjoint(hand) && (rest | hand) == CardSet::FULL && (rest & hand).is_empty());
+
+    // Drawing empties the set exactly once per card.
+    let mut rng = texas_equity_api::cards::seeded_rng(19);
+    let mut left = rest;
+    let mut seen = CardSet::EMPTY;
+    while let Some(i) = left.draw(&mut rng) {
+        assert!(seen.insert_idx(i) && rest.contains_idx(i));
+    }
+    assert_eq!(seen, rest);
+    let mut out = [0usize; 5];
+    hand.union(CardSet::from_indices(&[20, 30])).draw_into(&mut rng, &mut out);
+    assert_eq!(CardSet::from_indices(&out).len(), 5);
+}
+
+#[test]
+fn test_card_serde() {
+    let hand: Hand = "As Td 2c".parse().unwrap();
+    assert_eq!(hand.to_string(), "As Td 2c");
+    let json = serde_json::to_string(&hand).unwrap();
+    assert_eq!(json, r#"["As","Td","2c"]"#);
+    let back: Vec<Card> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, hand.0);
+    assert!(serde_json::from_str::<Card>(r#""Zz""#).is_err());
 }
 
 #[test]
 fn test_validate_hero_board() {
     let hero = vec![parse_card("As").unwrap(), parse_card("Kd").unwrap()];
     let board = vec![];
-    assert!(validate_input(&hero, &board).is_ok());
+    assert!(validate_input(Game::Holdem, &hero, &board, &[]).is_ok());
     let board_bad = vec![parse_card("7h").unwrap()];
-    assert!(validate_input(&hero, &board_bad).is_err());
+    assert!(validate_input(Game::Holdem, &hero, &board_bad, &[]).is_err());
+}
+
+#[test]
+fn test_simulate_against_range() {
+    use texas_equity_api::range::HandRange;
+    use texas_equity_api::simulate::{simulate_deal, Deal, Opponent};
+    let hero = vec![parse_card("As").unwrap(), parse_card("Ah").unwrap()];
+    let opponents = vec![Opponent::Range(HandRange::parse("KK").unwrap())];
+    let r = simulate_deal(&Deal::holdem(&hero, &[], &opponents), 20_000, Some(1));
+    assert_eq!(r.trials, 20_000);
+    assert!(r.win > 0.75 && r.win < 0.88, "AA vs KK win = {}", r.win);
+}
+
+#[test]
+fn test_simulate_known_opponents() {
+    use texas_equity_api::simulate::{simulate_deal, Deal, Opponent};
+    let c = |s: &str| parse_card(s).unwrap();
+    let hero = vec![c("As"), c("Kd")];
+    let board = vec![c("Qs"), c("7d"), c("2c")];
+    let opponents = vec![
+        Opponent::Known(vec![c("Qh"), c("Qc")]),
+        Opponent::Known(vec![c("7s"), c("7c")]),
+        Opponent::Random,
+    ];
+    let r = simulate_deal(&Deal::holdem(&hero, &board, &opponents), 20_000, Some(3));
+    assert_eq!(r.seats.len(), 4);
+    let total_win: f64 = r.seats.iter().map(|s| s.win).sum();
+    assert!(total_win <= 1.0 + 1e-9);
+    assert!((r.seats[0].win - r.win).abs() < 1e-12);
+    // Top set is far ahead of bottom set.
+    assert!(r.seats[1].win > r.seats[2].win * 3.0);
+}
+
+#[test]
+fn test_exact_matches_monte_carlo_on_turn() {
+    use texas_equity_api::cards::parse_cards;
+    use texas_equity_api::exact::enumerate;
+    use texas_equity_api::simulate::{simulate_deal, Deal, Opponent};
+    let hero = parse_cards("Ah Kh").unwrap();
+    let board = parse_cards("Qh 7h 2c 9d").unwrap();
+    let opponents = vec![Opponent::Random];
+    let ex = enumerate(&Deal::holdem(&hero, &board, &opponents));
+    assert_eq!(ex.trials, 46);
+    assert!((ex.win + ex.tie + ex.lose - 1.0).abs() < 1e-9);
+    let mc = simulate_deal(&Deal::holdem(&hero, &board, &opponents), 200_000, Some(5));
+    assert!((ex.win - mc.win).abs() < 0.01, "exact {} vs mc {}", ex.win, mc.win);
+}
+
+#[test]
+fn test_exact_with_blocked_ranges_records_nothing() {
+    use texas_equity_api::cards::parse_cards;
+    use texas_equity_api::exact::{enumerate, equity_auto, DEFAULT_EXACT_BUDGET};
+    use texas_equity_api::outs::analyze_outs;
+    use texas_equity_api::range::HandRange;
+    use texas_equity_api::simulate::{Deal, Opponent};
+    let aces = || Opponent::Range(HandRange::parse("AA").unwrap());
+    let hero = parse_cards("Ah Kh").unwrap();
+    // Every AA combo is blocked by hero and the board.
+    let board = parse_cards("As Ad 7h 2c").unwrap();
+    let ex = enumerate(&Deal::holdem(&hero, &board, &[aces()]));
+    assert_eq!(ex.trials, 0);
+    // Three aces left, so two AA hands cannot be dealt together.
+    let board = parse_cards("Qh 7h 2c 9d").unwrap();
+    let deal = Deal::holdem(&hero, &board, &[aces(), aces()]);
+    let (r, method) = equity_auto(&deal, DEFAULT_EXACT_BUDGET, 10_000, Some(1));
+    assert_eq!((method, r.trials), ("exact", 0));
+    assert_eq!(analyze_outs(&deal, DEFAULT_EXACT_BUDGET, 10_000, Some(1)).current.trials, 0);
+}
+
+#[test]
+fn test_hand_category_distribution() {
+    use texas_equity_api::cards::parse_cards;
+    use texas_equity_api::evaluate::HandCategory;
+    use texas_equity_api::exact::enumerate;
+    use texas_equity_api::simulate::{Deal, Opponent};
+    // Four to the flush on the turn: 9 hearts of 46 river cards make hero a flush.
+    let hero = parse_cards("Ah Kh").unwrap();
+    let board = parse_cards("Qh 7h 2c 9d").unwrap();
+    let r = enumerate(&Deal::holdem(&hero, &board, &[Opponent::Random]));
+    let flush = HandCategory::Flush.index();
+    assert!((r.hero_categories[flush] - 9.0 / 46.0).abs() < 1e-9);
+    assert!((r.hero_categories.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    assert!((r.opponent_categories.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_standard_error_covers_exact_value() {
+    use texas_equity_api::cards::parse_cards;
+    use texas_equity_api::exact::enumerate;
+    use texas_equity_api::simulate::{simulate_deal, Deal, Opponent};
+    let hero = parse_cards("Jc Tc").unwrap();
+    let board = parse_cards("9c 8d 2h 3s").unwrap();
+    let opponents = vec![Opponent::Random];
+    let ex = enumerate(&Deal::holdem(&hero, &board, &opponents));
+    assert_eq!(ex.std_err.win, 0.0);
+    let mc = simulate_deal(&Deal::holdem(&hero, &board, &opponents), 50_000, Some(11));
+    let se = mc.std_err;
+    assert!((se.win - (mc.win * (1.0 - mc.win) / 50_000.0).sqrt()).abs() < 1e-12);
+    assert!(se.equity > 0.0 && se.equity <= 0.5 / (50_000f64).sqrt());
+    assert!((mc.win - ex.win).abs() < 4.0 * se.win, "mc {} exact {} se {}", mc.win, ex.win, se.win);
+    let ci = mc.ci95();
+    assert!(ci.equity[0] < mc.equity && mc.equity < ci.equity[1]);
+}
+
+#[test]
+fn test_adaptive_stops_at_target_precision() {
+    use texas_equity_api::cards::parse_cards;
+    use texas_equity_api::simulate::{simulate_adaptive, Deal, Opponent, StopReason, StopRule};
+    let hero = parse_cards("Ah Kh").unwrap();
+    let board = parse_cards("Qh 7h 2c").unwrap();
+    let deal = Deal::holdem(&hero, &board, &[Opponent::Random, Opponent::Random]);
+    let rule = StopRule {
+        target_std_err: Some(0.003),
+        deadline: None,
+        max_trials: 5_000_000,
+    };
+    let (r, reason) = simulate_adaptive(&deal, rule, Some(9));
+    assert_eq!(reason, StopReason::Precision);
+    assert!(r.std_err.equity <= 0.003);
+    // A looser target must need fewer trials.
+    let loose = StopRule {
+        target_std_err: Some(0.01),
+        ..rule
+    };
+    let (r2, _) = simulate_adaptive(&deal, loose, Some(9));
+    assert!(r2.trials < r.trials, "{} vs {}", r2.trials, r.trials);
+
+    let capped = StopRule {
+        target_std_err: Some(1e-6),
+        deadline: None,
+        max_trials: 30_000,
+    };
+    let (r3, reason) = simulate_adaptive(&deal, capped, Some(9));
+    assert_eq!(reason, StopReason::MaxTrials);
+    assert_eq!(r3.trials, 30_000);
+}
+
+#[test]
+fn test_progress_reports_rounds_and_cancels() {
+    use texas_equity_api::cards::parse_cards;
+    use texas_equity_api::simulate::{simulate_adaptive, simulate_with_progress, Deal, Opponent, StopReason, StopRule};
+    let hero = parse_cards("Ah Kh").unwrap();
+    let board = parse_cards("Qh 7h 2c").unwrap();
+    let deal = Deal::holdem(&hero, &board, &[Opponent::Random]);
+    let rule = StopRule {
+        target_std_err: None,
+        deadline: None,
+        max_trials: 50_000,
+    };
+    let mut seen = Vec::new();
+    let (r, reason) = simulate_with_progress(&deal, rule, Some(9), |p| {
+        seen.push((p.trials, p.std_err.equity));
+        true
+    });
+    assert_eq!(reason, StopReason::MaxTrials);
+    assert_eq!(seen.iter().map(|s| s.0).collect::<Vec<_>>(), [10_000, 20_000, 30_000, 40_000, 50_000]);
+    // the estimate converges and the last report is the final result
+    assert!(seen[4].1 < seen[0].1);
+    let (plain, _) = simulate_adaptive(&deal, rule, Some(9));
+    assert_eq!((r.win, r.tie, r.trials), (plain.win, plain.tie, plain.trials));
+
+    let (r, reason) = simulate_with_progress(&deal, rule, Some(9), |p| p.trials < 20_000);
+    assert_eq!(reason, StopReason::Cancelled);
+    assert_eq!(r.trials, 20_000);
+}
+
+#[test]
+fn test_seeded_results_independent_of_thread_count() {
+    use texas_equity_api::rng::{RngAlgorithm, RngConfig};
+    use texas_equity_api::simulate::{simulate_adaptive, simulate_deal, Deal, Opponent, StopRule};
+    let hero = parse_cards("Ah Kh").unwrap();
+    let board = parse_cards("Qh 7h 2c").unwrap();
+    let range = texas_equity_api::range::HandRange::parse("TT+, AQs+").unwrap();
+    let deal = Deal::holdem(&hero, &board, &[Opponent::Random, Opponent::Range(range), Opponent::Random]);
+    let rule = StopRule {
+        target_std_err: Some(0.004),
+        deadline: None,
+        max_trials: 1_000_000,
+    };
+    // Everything a seeded run reports, as raw bits so any float difference shows up.
+    let fingerprint = |threads: usize, algorithm: RngAlgorithm| {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+        pool.install(|| {
+            let rng = RngConfig::new(algorithm, Some(2024));
+            let r = simulate_deal(&deal, 25_500, rng);
+            let (a, _) = simulate_adaptive(&deal, rule, rng);
+            let mut bits = vec![r.trials, a.trials];
+            for x in [r.win, r.tie, r.lose, r.equity, r.std_err.equity, a.win, a.tie, a.equity] {
+                bits.push(x.to_bits());
+            }
+            bits.extend(r.seats.iter().map(|s| s.equity.to_bits()));
+            bits.extend(r.hero_categories.iter().map(|x| x.to_bits()));
+            bits
+        })
+    };
+    for algorithm in RngAlgorithm::ALL {
+        let one = fingerprint(1, algorithm);
+        for threads in [2, 3, 8] {
+            assert_eq!(fingerprint(threads, algorithm), one, "{:?} on {} threads", algorithm, threads);
+        }
+    }
+}
+
+#[test]
+fn test_three_way_chop_is_third_of_pot() {
+    use texas_equity_api::cards::parse_cards;
+    use texas_equity_api::exact::enumerate;
+    use texas_equity_api::simulate::{simulate_deal, Deal, Opponent};
+    // Royal flush on the board: everyone plays the board.
+    let hero = parse_cards("2c 3d").unwrap();
+    let board = parse_cards("As Ks Qs Js Ts").unwrap();
+    let opp = parse_cards("4h 5h 6c 7d").unwrap();
+    let known = vec![Opponent::Known(opp[..2].to_vec()), Opponent::Known(opp[2..].to_vec())];
+    let ex = enumerate(&Deal::holdem(&hero, &board, &known));
+    assert_eq!(ex.tie, 1.0);
+    assert!((ex.equity - 1.0 / 3.0).abs() < 1e-12);
+    let mc = simulate_deal(&Deal::holdem(&hero, &board, &[Opponent::Random, Opponent::Random]), 2_000, Some(1));
+    assert!((mc.equity - 1.0 / 3.0).abs() < 1e-12);
+    assert!(mc.std_err.equity < 1e-6);
+}
+
+#[test]
+fn test_per_seat_pot_shares() {
+    use texas_equity_api::cards::parse_cards;
+    use texas_equity_api::simulate::{simulate_deal, Deal, Opponent};
+    let hero = parse_cards("8h 8d").unwrap();
+    let board = parse_cards("8s 5c 6c").unwrap();
+    let opponents = vec![Opponent::Random; 5];
+    let r = simulate_deal(&Deal::holdem(&hero, &board, &opponents), 20_000, Some(4));
+    assert_eq!(r.seats.len(), 6);
+    let total: f64 = r.seats.iter().map(|s| s.equity).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+    assert!((r.seats[0].equity - r.equity).abs() < 1e-12);
+    for s in &r.seats {
+        assert!((s.win + s.tie + s.lose - 1.0).abs() < 1e-9);
+        assert!(s.win <= s.equity && s.equity <= s.win + s.tie / 2.0 + 1e-12);
+    }
+    // Random opponents are exchangeable.
+    for s in &r.seats[2..] {
+        assert!((s.equity - r.seats[1].equity).abs() < 0.02);
+    }
+}
+
+#[test]
+fn test_dead_cards_are_not_dealt() {
+    use texas_equity_api::cards::parse_cards;
+    use texas_equity_api::evaluate::HandCategory;
+    use texas_equity_api::exact::enumerate;
+    use texas_equity_api::simulate::{simulate_deal, Deal, Opponent};
+    let hero = parse_cards("Ah Kh").unwrap();
+    let board = parse_cards("Qh 7h 2c 9d").unwrap();
+    // Every remaining heart is exposed: no flush can come.
+    let dead = parse_cards("2h 3h 4h 5h 6h 8h 9h Th Jh").unwrap();
+    let dead_deal = Deal {
+        dead: dead.clone(),
+        ..Deal::holdem(&hero, &board, &[Opponent::Random])
+    };
+    let ex = enumerate(&dead_deal);
+    assert_eq!(ex.trials, 46 - 9);
+    assert_eq!(ex.hero_categories[HandCategory::Flush.index()], 0.0);
+    let mc = simulate_deal(&dead_deal, 20_000, Some(2));
+    assert_eq!(mc.hero_categories[HandCategory::Flush.index()], 0.0);
+    assert!(validate_input(Game::Holdem, &hero, &board, &parse_cards("Ah").unwrap()).is_err());
+    assert!(validate_input(Game::Holdem, &hero, &board, &dead).is_ok());
+}
+
+#[test]
+fn test_omaha_exact_matches_monte_carlo() {
+    use texas_equity_api::cards::parse_cards;
+    use texas_equity_api::exact::{enumerate, state_count};
+    use texas_equity_api::simulate::{simulate_deal, Deal, Opponent};
+    let deal = Deal {
+        game: Game::Plo4,
+        hero: parse_cards("Ah Kh Qd Jd").unwrap(),
+        board: parse_cards("Th 9h 2c 3s").unwrap(),
+        opponents: vec![Opponent::Known(parse_cards("As Ks 8c 8d").unwrap())],
+        dead: Vec::new(),
+    };
+    assert_eq!(state_count(&deal), 40);
+    let ex = enumerate(&deal);
+    assert_eq!(ex.trials, 40);
+    let mc = simulate_deal(&deal, 50_000, Some(6));
+    assert!((ex.equity - mc.equity).abs() < 4.0 * mc.std_err.equity + 1e-3, "exact {} vs mc {}", ex.equity, mc.equity);
+
+    // A random PLO opponent holds four cards.
+    let random = Deal {
+        opponents: vec![Opponent::Random],
+        ..deal
+    };
+    assert_eq!(state_count(&random), 44 * 123_410);
+    let r = simulate_deal(&random, 20_000, Some(6));
+    assert!((r.win + r.tie + r.lose - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_omaha_hi_lo_split_pot() {
+    use texas_equity_api::cards::parse_cards;
+    use texas_equity_api::exact::{enumerate, state_count};
+    use texas_equity_api::simulate::{simulate_deal, Deal, Opponent};
+    // Both hold A-3 for the same 7-5-3-2-A low; trip kings beat trip nines for the high.
+    let deal = Deal {
+        game: Game::Plo4HiLo,
+        hero: parse_cards("Ac 3d 9s 9h").unwrap(),
+        board: parse_cards("2c 5d 7h Kd 9c").unwrap(),
+        opponents: vec![Opponent::Known(parse_cards("As 3h Kh Ks").unwrap())],
+        dead: Vec::new(),
+    };
+    let r = enumerate(&deal);
+    let hi_lo = r.hi_lo.expect("hi-lo stats");
+    assert!((r.equity - 0.25).abs() < 1e-9, "equity {}", r.equity);
+    assert!((r.seats[1].equity - 0.75).abs() < 1e-9);
+    assert!((hi_lo.quartered - 1.0).abs() < 1e-9);
+    assert_eq!((hi_lo.scoop, hi_lo.no_low), (0.0, 0.0));
+    assert!((r.tie - 1.0).abs() < 1e-9);
+
+    // Only one low card on board: nobody has a low and the high scoops.
+    let no_low = Deal {
+        board: parse_cards("Kd Qs Jh 9c 2c").unwrap(),
+        ..deal.clone()
+    };
+    let r = enumerate(&no_low);
+    let hi_lo = r.hi_lo.unwrap();
+    assert_eq!((r.equity, r.lose, hi_lo.no_low), (0.0, 1.0, 1.0));
+    assert!((r.seats[1].equity - 1.0).abs() < 1e-9);
+
+    // Turn: exact over 40 rivers agrees with Monte Carlo, and the pot is fully shared.
+    let turn = Deal {
+        board: parse_cards("2c 5d 7h Kd").unwrap(),
+        ..deal
+    };
+    assert_eq!(state_count(&turn), 40);
+    let ex = enumerate(&turn);
+    assert!((ex.seats.iter().map(|s| s.equity).sum::<f64>() - 1.0).abs() < 1e-9);
+    let mc = simulate_deal(&turn, 40_000, Some(16));
+    assert!((ex.equity - mc.equity).abs() < 4.0 * mc.std_err.equity + 1e-3, "exact {} vs mc {}", ex.equity, mc.equity);
+    let (e, m) = (ex.hi_lo.unwrap(), mc.hi_lo.unwrap());
+    assert!((e.scoop - m.scoop).abs() < 0.02 && (e.quartered - m.quartered).abs() < 0.02);
+    assert!(simulate_deal(&Deal { game: Game::Plo4, ..turn }, 100, Some(1)).hi_lo.is_none());
+}
+
+#[test]
+fn test_stud_and_razz() {
+    use texas_equity_api::cards::parse_cards;
+    use texas_equity_api::exact::{enumerate, state_count};
+    use texas_equity_api::simulate::{simulate_deal, Deal, Opponent};
+    // Stud has no board and hands start at three cards.
+    assert!(validate_input(Game::Stud, &parse_cards("As Ad Kc").unwrap(), &[], &[]).is_ok());
+    assert!(validate_input(Game::Stud, &parse_cards("As Ad").unwrap(), &[], &[]).is_err());
+    assert!(validate_input(Game::Stud, &parse_cards("As Ad Kc").unwrap(), &parse_cards("2c 3d 4h").unwrap(), &[])
+        .is_err());
+
+    // Sixth street: hero's river card and the opponent's hidden cards are unknown.
+    let deal = Deal {
+        game: Game::Stud,
+        hero: parse_cards("As Ad Kc Kd 7h 2s").unwrap(),
+        board: Vec::new(),
+        opponents: vec![Opponent::Known(parse_cards("Qs Qh Jc 9d 8c").unwrap())],
+        dead: parse_cards("Qd").unwrap(),
+    };
+    assert_eq!(state_count(&deal), 40 * 741);
+    let ex = enumerate(&deal);
+    assert_eq!(ex.trials, 40);
+    let mc = simulate_deal(&deal, 50_000, Some(17));
+    assert!((ex.equity - mc.equity).abs() < 4.0 * mc.std_err.equity + 1e-3, "exact {} vs mc {}", ex.equity, mc.equity);
+    assert!(ex.equity > 0.5 && ex.hi_lo.is_none());
+
+    // Razz: a made wheel never loses, and unknown opponents are dealt all seven cards.
+    let razz = Deal {
+        game: Game::Razz,
+        hero: parse_cards("As 2d 3c 4h 5s Kd Kc").unwrap(),
+        opponents: vec![Opponent::Random, Opponent::Known(parse_cards("Ah 2h 3h").unwrap())],
+        dead: Vec::new(),
+        ..deal.clone()
+    };
+    let r = simulate_deal(&razz, 20_000, Some(17));
+    assert_eq!(r.lose, 0.0);
+    assert!(r.win > 0.9);
+
+    // Stud 8-or-better reports the split.
+    let stud8 = Deal {
+        game: Game::Stud8,
+        hero: parse_cards("As 2d 3c Kd 7h").unwrap(),
+        opponents: vec![Opponent::Known(parse_cards("4s 5h 6c Jd").unwrap())],
+        ..deal
+    };
+    let r = simulate_deal(&stud8, 10_000, Some(17));
+    let hi_lo = r.hi_lo.expect("hi-lo stats");
+    assert!(hi_lo.no_low > 0.0 && hi_lo.no_low < 1.0);
+    assert!((r.seats.iter().map(|s| s.equity).sum::<f64>() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_short_deck_deal() {
+    use texas_equity_api::cards::parse_cards;
+    use texas_equity_api::evaluate::HandCategory;
+    use texas_equity_api::exact::{enumerate, state_count};
+    use texas_equity_api::range::HandRange;
+    use texas_equity_api::simulate::{simulate_deal, Deal, Opponent};
+    let hero = parse_cards("Ah Kh").unwrap();
+    let board = parse_cards("Qh 7h 6c 9d").unwrap();
+    assert!(validate_input(Game::ShortDeck, &hero, &parse_cards("Qh 7h 2c").unwrap(), &[]).is_err());
+    let deal = Deal {
+        game: Game::ShortDeck,
+        ..Deal::holdem(&hero, &board, &[Opponent::Random])
+    };
+    // 36 - 6 known cards: 30 rivers, then C(29, 2) opponent hands.
+    assert_eq!(state_count(&deal), 30 * 406);
+    let ex = enumerate(&deal);
+    assert_eq!(ex.trials, 30);
+    // 5 hearts left among 30 rivers make hero a flush.
+    assert!((ex.hero_categories[HandCategory::Flush.index()] - 5.0 / 30.0).abs() < 1e-9);
+    let mc = simulate_deal(&deal, 50_000, Some(8));
+    assert!((ex.equity - mc.equity).abs() < 4.0 * mc.std_err.equity + 1e-3);
+    // Ranges drop combos outside the short deck.
+    let ranged = Deal {
+        opponents: vec![Opponent::Range(HandRange::parse("22-55").unwrap())],
+        ..deal
+    };
+    assert_eq!(simulate_deal(&ranged, 1_000, Some(1)).trials, 0);
+}
+
+#[test]
+fn test_variance_reduction() {
+    use texas_equity_api::exact::enumerate;
+    use texas_equity_api::range::HandRange;
+    use texas_equity_api::simulate::{simulate_compare, simulate_deal, Deal, Opponent, SimOptions};
+    let hero = parse_cards("9s 8s").unwrap();
+    let board = parse_cards("Ts 7d 2c").unwrap();
+    let deal = Deal::holdem(&hero, &board, &[Opponent::Random]);
+    let ex = enumerate(&deal);
+    let stratified = SimOptions {
+        stratify: true,
+        ..Some(12).into()
+    };
+    let plain = simulate_deal(&deal, 40_000, Some(12));
+    let st = simulate_deal(&deal, 40_000, stratified);
+    assert!(st.stratified && !plain.stratified);
+    assert_eq!(st.trials, 40_000);
+    assert!((ex.equity - st.equity).abs() < 4.0 * st.std_err.equity, "{} vs {}", ex.equity, st.equity);
+    assert!((ex.win - st.win).abs() < 4.0 * st.std_err.win);
+    // Conditioning on turn and river removes a good part of the noise on a draw-heavy flop.
+    assert!(st.std_err.equity < 0.8 * plain.std_err.equity, "{:?} vs {:?}", st.std_err, plain.std_err);
+
+    // A ranged opponent changes which board cards are likely, so no stratification.
+    let ranged = Deal::holdem(&hero, &board, &[Opponent::Range(HandRange::parse("TT+").unwrap())]);
+    assert!(!simulate_deal(&ranged, 2_000, stratified).stratified);
+
+    // Common random numbers: AsKd and AsKc have the same equity and, sharing every
+    // runout that avoids the kings, nearly the same estimate.
+    let offsuit = |h: &str| Deal::holdem(&parse_cards(h).unwrap(), &[], &[Opponent::Random, Opponent::Random]);
+    let r = simulate_compare(&[offsuit("As Kd"), offsuit("As Kc"), offsuit("As Kd")], 20_000, Some(3));
+    assert_eq!(r[0].equity, r[2].equity);
+    assert!((r[0].equity - r[1].equity).abs() < 0.2 * r[0].std_err.equity, "{} vs {}", r[0].equity, r[1].equity);
+}
+
+#[test]
+fn test_stratified_fast_against_plain_high() {
+    use texas_equity_api::exact::enumerate;
+    use texas_equity_api::simulate::{simulate_deal, trials_for_preset, Deal, Opponent, SimOptions};
+    let stratified = SimOptions {
+        stratify: true,
+        ..Some(7).into()
+    };
+    let (fast, high) = (trials_for_preset("fast"), trials_for_preset("high"));
+    let hero = parse_cards("9s 8s").unwrap();
+    let board = parse_cards("Ts 7d 2c").unwrap();
+    // With the opponent's hand known, the turn and river are the only noise: every stratum
+    // is decided, so "fast" stratified is exact and beats "high" plain.
+    let known = Deal::holdem(&hero, &board, &[Opponent::Known(parse_cards("Ah Td").unwrap())]);
+    let st = simulate_deal(&known, fast, stratified);
+    let plain = simulate_deal(&known, high, Some(7));
+    assert!((st.equity - enumerate(&known).equity).abs() < 1e-9);
+    assert!(st.std_err.equity <= plain.std_err.equity);
+    // Against a random hand most of the noise is the opponent's holding, which strata do
+    // not cover: "fast" stratified stays well short of "high" plain.
+    let random = Deal::holdem(&hero, &board, &[Opponent::Random]);
+    let st = simulate_deal(&random, fast, stratified);
+    let plain = simulate_deal(&random, high, Some(7));
+    assert!(st.std_err.equity > 2.0 * plain.std_err.equity, "{} vs {}", st.std_err.equity, plain.std_err.equity);
+    assert!((st.equity - plain.equity).abs() < 4.0 * st.std_err.equity);
 }
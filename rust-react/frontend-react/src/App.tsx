@@ -1,4 +1,4 @@
-import { useState, useEffect } from 'react'
+import { useState, useEffect, useRef } from 'react'
 
 const RANKS = 'AKQJT98765432'
 const SUITS = 'shdc'
@@ -28,8 +28,52 @@ interface Result {
   trials: number
   elapsedMs?: number
   elapsed_ms?: number
-  method?: 'preflop_table' | 'monte_carlo'
+  method?: 'preflop_table' | 'monte_carlo' | 'exact'
   note?: string
+  handCategories?: { category: string; hero: number; opponent: number }[]
+  equity?: number
+  stdErr?: { win: number; tie: number; lose: number; equity: number }
+}
+
+// Reads a text/event-stream response, calling onEvent with each event's name and JSON data.
+async function readEvents(res: Response, onEvent: (event: string, data: unknown) => void) {
+  const reader = res.body!.getReader()
+  const decoder = new TextDecoder()
+  let buf = ''
+  for (;;) {
+    const { done, value } = await reader.read()
+    if (done) return
+    buf += decoder.decode(value, { stream: true })
+    let end
+    while ((end = buf.indexOf('\n\n')) >= 0) {
+      const block = buf.slice(0, end)
+      buf = buf.slice(end + 2)
+      let event = 'message'
+      const data: string[] = []
+      for (const line of block.split('\n')) {
+        if (line.startsWith('event:')) event = line.slice(6).trim()
+        else if (line.startsWith('data:')) data.push(line.slice(5).trimStart())
+      }
+      // keep-alive comments carry no data
+      if (data.length > 0) onEvent(event, JSON.parse(data.join('\n')))
+    }
+  }
+}
+
+function formatMargin(se: number | undefined): string {
+  return se ? ` (±${(1.96 * se * 100).toFixed(2)})` : ''
+}
+
+const CATEGORY_LABELS: Record<string, string> = {
+  straight_flush: 'ストレートフラッシュ',
+  four_of_a_kind: 'フォーカード',
+  full_house: 'フルハウス',
+  flush: 'フラッシュ',
+  straight: 'ストレート',
+  three_of_a_kind: 'スリーカード',
+  two_pair: 'ツーペア',
+  one_pair: 'ワンペア',
+  high_card: 'ハイカード',
 }
 
 function isRedSuit(suit: string): boolean {
@@ -93,6 +137,9 @@ export default function App() {
   const [loading, setLoading] = useState(false)
   const [result, setResult] = useState<Result | null>(null)
   const [error, setError] = useState<string | null>(null)
+  // true while result holds an interim estimate from a progress event
+  const [interim, setInterim] = useState(false)
+  const abortRef = useRef<AbortController | null>(null)
 
   function setHeroCard(i: number, field: 'rank' | 'suit', value: string) {
     setHeroCards((prev) => {
@@ -130,6 +177,9 @@ export default function App() {
       return
     }
     setLoading(true)
+    setInterim(false)
+    const controller = new AbortController()
+    abortRef.current = controller
     const body: Record<string, unknown> = {
       players,
       hero,
@@ -137,24 +187,42 @@ export default function App() {
       preset,
     }
     try {
-      const res = await fetch('/api/equity', {
+      const res = await fetch('/api/equity/stream', {
         method: 'POST',
         headers: { 'Content-Type': 'application/json' },
         body: JSON.stringify(body),
+        signal: controller.signal,
       })
-      const data = await res.json()
       if (!res.ok) {
+        const data = await res.json()
         setError((data as { details?: string; error?: string }).details || (data as { error?: string }).error || `Error ${res.status}`)
         return
       }
-      setResult(data as Result)
+      await readEvents(res, (event, data) => {
+        if (event === 'progress') {
+          setResult(data as Result)
+          setInterim(true)
+        } else if (event === 'result') {
+          setResult(data as Result)
+          setInterim(false)
+        } else if (event === 'error') {
+          setError((data as { details?: string; error?: string }).details || (data as { error?: string }).error || 'error')
+          setResult(null)
+        }
+      })
     } catch (err) {
-      setError(String(err))
+      // on cancel, keep the last interim estimate on screen
+      if (!controller.signal.aborted) setError(String(err))
     } finally {
+      abortRef.current = null
       setLoading(false)
     }
   }
 
+  function handleCancel() {
+    abortRef.current?.abort()
+  }
+
   const sectionStyle = { marginTop: '1.5rem', marginBottom: '0.5rem' }
   const labelBlock = { display: 'flex', alignItems: 'center', gap: '0.5rem', marginBottom: '0.25rem' } as const
   const selectStyle = { fontSize: '1.1rem', padding: '0.35rem 0.5rem', minHeight: '2.25rem' } as const
@@ -319,9 +387,16 @@ export default function App() {
             <option value="high">高精度（1,000,000通り）</option>
           </select>
         </label>
-        <button type="submit" disabled={loading} style={{ marginTop: '0.5rem', padding: '0.5rem 1rem', alignSelf: 'flex-start' }}>
-          {loading ? '計算中…' : '計算'}
-        </button>
+        <div style={{ display: 'flex', gap: '0.5rem', marginTop: '0.5rem' }}>
+          <button type="submit" disabled={loading} style={{ padding: '0.5rem 1rem' }}>
+            {loading ? '計算中…' : '計算'}
+          </button>
+          {loading && (
+            <button type="button" onClick={handleCancel} style={{ padding: '0.5rem 1rem' }}>
+              キャンセル
+            </button>
+          )}
+        </div>
       </form>
 
       {error && (
@@ -338,21 +413,56 @@ export default function App() {
               プリフロップ固定表を使用
             </p>
           )}
+          {interim && (
+            <p style={{ marginBottom: '0.5rem', color: '#e65100', fontSize: '0.9rem', fontWeight: 600 }}>
+              {loading ? '途中経過（計算中）' : 'キャンセルしました（途中結果）'}
+            </p>
+          )}
+          {result.method === 'exact' && (
+            <p style={{ marginBottom: '0.5rem', color: '#2e7d32', fontSize: '0.9rem', fontWeight: 600 }}>
+              全通り列挙（厳密値）
+            </p>
+          )}
           <p style={{ fontSize: '1.25rem', fontWeight: 700, margin: '0.25rem 0' }}>
-            Win: {(result.win * 100).toFixed(2)}%
+            Win: {(result.win * 100).toFixed(2)}%{formatMargin(result.stdErr?.win)}
           </p>
           <p style={{ fontSize: '1.25rem', fontWeight: 700, margin: '0.25rem 0' }}>
-            Tie: {(result.tie * 100).toFixed(2)}%
+            Tie: {(result.tie * 100).toFixed(2)}%{formatMargin(result.stdErr?.tie)}
           </p>
           <p style={{ fontSize: '1.25rem', fontWeight: 700, margin: '0.25rem 0' }}>
-            Lose: {(result.lose * 100).toFixed(2)}%
+            Lose: {(result.lose * 100).toFixed(2)}%{formatMargin(result.stdErr?.lose)}
           </p>
+          {result.equity !== undefined && (
+            <p style={{ margin: '0.25rem 0' }}>
+              Equity: {(result.equity * 100).toFixed(2)}%{formatMargin(result.stdErr?.equity)}
+            </p>
+          )}
           <p style={{ marginTop: '0.75rem', color: '#555', fontSize: '0.9rem' }}>
             試行回数: {result.trials.toLocaleString()} / 計算時間: {(result.elapsedMs ?? result.elapsed_ms ?? 0)} ms
           </p>
           {result.note && (
             <p style={{ marginTop: '0.5rem', color: '#666', fontSize: '0.85rem' }}>{result.note}</p>
           )}
+          {result.handCategories && (
+            <table style={{ marginTop: '0.75rem', fontSize: '0.85rem', borderCollapse: 'collapse' }}>
+              <thead>
+                <tr>
+                  <th style={{ textAlign: 'left', paddingRight: '1rem' }}>役</th>
+                  <th style={{ textAlign: 'right', paddingRight: '1rem' }}>自分</th>
+                  <th style={{ textAlign: 'right' }}>相手最強</th>
+                </tr>
+              </thead>
+              <tbody>
+                {result.handCategories.map((c) => (
+                  <tr key={c.category}>
+                    <td style={{ paddingRight: '1rem' }}>{CATEGORY_LABELS[c.category] ?? c.category}</td>
+                    <td style={{ textAlign: 'right', paddingRight: '1rem' }}>{(c.hero * 100).toFixed(2)}%</td>
+                    <td style={{ textAlign: 'right' }}>{(c.opponent * 100).toFixed(2)}%</td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          )}
         </div>
       )}
     </main>